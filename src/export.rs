@@ -0,0 +1,62 @@
+//! Compressed batch encoding for uploads.
+//!
+//! Batches are encoded as a JSON array and gzip-compressed, which typically
+//! shrinks repetitive sensor streams by an order of magnitude.
+
+use crate::services::SensorError;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{Read, Write};
+
+/// JSON-encodes `samples` as an array and gzip-compresses the result.
+pub fn to_gzip_json<T: Serialize>(samples: &[T]) -> Result<Vec<u8>, SensorError> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    serde_json::to_writer(&mut encoder, samples)
+        .map_err(|e| SensorError::DataError(e.to_string()))?;
+    encoder
+        .flush()
+        .and_then(|_| encoder.finish())
+        .map_err(|e| SensorError::DataError(e.to_string()))
+}
+
+/// Inverse of [`to_gzip_json`].
+pub fn from_gzip_json<T: DeserializeOwned>(bytes: &[u8]) -> Result<Vec<T>, SensorError> {
+    let mut json = Vec::new();
+    GzDecoder::new(bytes)
+        .read_to_end(&mut json)
+        .map_err(|e| SensorError::DataError(e.to_string()))?;
+    serde_json::from_slice(&json).map_err(|e| SensorError::DataError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks::*;
+    use crate::models::AccelerometerData;
+
+    #[test]
+    fn test_gzip_json_round_trip() {
+        let samples: Vec<AccelerometerData> = (0..200)
+            .map(|i| AccelerometerData {
+                timestamp: i,
+                ..mock_accelerometer_moving()
+            })
+            .collect();
+
+        let compressed = to_gzip_json(&samples).unwrap();
+        let plain = serde_json::to_vec(&samples).unwrap();
+        assert!(compressed.len() < plain.len() / 4);
+
+        let decoded: Vec<AccelerometerData> = from_gzip_json(&compressed).unwrap();
+        assert_eq!(decoded, samples);
+    }
+
+    #[test]
+    fn test_from_gzip_json_rejects_garbage() {
+        let result = from_gzip_json::<AccelerometerData>(b"not gzip");
+        assert!(matches!(result, Err(SensorError::DataError(_))));
+    }
+}
@@ -0,0 +1,34 @@
+//! Core library for the mobile sensor proof of concept.
+//!
+//! The crate is split into these layers:
+//!
+//! - [`models`]: plain serializable data types for each sensor reading.
+//! - [`mocks`]: canned readings used by the UI in the browser and by tests.
+//! - [`math`]: pure geodesy and signal math shared by the services.
+//! - `export` (feature `gzip`): compressed batch encoding for uploads.
+//! - [`services`]: [`SensorService`], which validates, formats and derives
+//!   values from sensor readings.
+
+#[cfg(feature = "gzip")]
+pub mod export;
+pub mod math;
+pub mod mocks;
+pub mod models;
+pub mod services;
+
+pub use models::{
+    parse_nmea_gga, parse_nmea_gga_with_quality, AccelerometerData, AccelerometerDataBuilder,
+    Accuracy, Axis, DirectionStyle, GpsData, GpsDataBuilder, GyroscopeData, GyroscopeDataBuilder,
+    HasTimestamp, HumidityData, HumidityDataBuilder, ImuData, MagnetometerData,
+    MagnetometerDataBuilder, PressureData, PressureDataBuilder, SensorReading, SensorSession,
+    SensorSnapshot, SensorSnapshotBuilder, SensorType, SessionMetadata, TemperatureData,
+    TemperatureDataBuilder, UnitAnnotated, WifiNetwork,
+};
+#[cfg(feature = "async")]
+pub use services::SessionPlayer;
+pub use services::{
+    EmaFilter, GpsKalmanFilter, LowPassFilter, MahonyFilter, MedianFilter, MergeStrategy,
+    MovingAverageFilter, NoiseFilter, OutlierRejector, PressureTrend, RollingStats, RouteSummary,
+    SampleSink, SensorError, SensorService, ShakeDetector, StepDetector, StreamingMedian,
+    TrackRecorder, TurnDetector, TurnDirection, VecSink, WifiBand, WriterSink,
+};
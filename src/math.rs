@@ -0,0 +1,263 @@
+//! Pure sensor math, usable without constructing a [`SensorService`].
+//!
+//! [`SensorService`] methods delegate to these functions.
+//!
+//! [`SensorService`]: crate::SensorService
+
+/// Mean Earth radius in meters, used for spherical-earth geodesy.
+pub const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// Wraps any angle in degrees into the range [0, 360).
+pub(crate) fn normalize_heading(heading: f32) -> f32 {
+    let normalized = heading.rem_euclid(360.0);
+    // rem_euclid can round up to exactly 360.0 for tiny negative inputs.
+    if normalized >= 360.0 {
+        0.0
+    } else {
+        normalized
+    }
+}
+
+/// Computes a compass heading from the horizontal magnetometer components.
+///
+/// Assumes the device is lying flat. The result is normalized to 0-359 degrees.
+pub fn calculate_heading(x: f32, y: f32) -> f32 {
+    let heading = y.atan2(x).to_degrees();
+    normalize_heading(heading)
+}
+
+/// Length of a 3D vector, e.g. the acceleration magnitude in m/s².
+pub fn magnitude(x: f32, y: f32, z: f32) -> f32 {
+    (x * x + y * y + z * z).sqrt()
+}
+
+/// Great-circle distance in meters between two points given in degrees.
+pub fn haversine(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = phi2 - phi1;
+    let d_lambda = (lon2 - lon1).to_radians();
+    let h = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * h.sqrt().min(1.0).asin()
+}
+
+/// Initial great-circle bearing (forward azimuth) in degrees, 0-360, from the
+/// first point to the second.
+pub fn bearing(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_lambda = (lon2 - lon1).to_radians();
+    let y = d_lambda.sin() * phi2.cos();
+    let x = phi1.cos() * phi2.sin() - phi1.sin() * phi2.cos() * d_lambda.cos();
+    let degrees = y.atan2(x).to_degrees().rem_euclid(360.0);
+    if degrees >= 360.0 {
+        0.0
+    } else {
+        degrees
+    }
+}
+
+/// Compass heading in degrees (0-359) corrected for device tilt.
+///
+/// `mag` and `accel` are device-frame `[x, y, z]` vectors. Roll and pitch are
+/// estimated from the gravity vector (roll = atan2(ay, az),
+/// pitch = atan2(-ax, ay·sin(roll) + az·cos(roll))) and the magnetic vector is
+/// rotated back into the horizontal plane before taking the heading. When
+/// the acceleration is near zero (free fall) there is no gravity reference,
+/// so the flat-device heading is returned instead.
+pub fn tilt_compensated_heading(mag: [f32; 3], accel: [f32; 3]) -> f32 {
+    /// Below this magnitude (m/s²) the gravity direction is unreliable.
+    const MIN_GRAVITY: f32 = 1.0;
+    let [mx, my, mz] = mag;
+    let [ax, ay, az] = accel;
+    if magnitude(ax, ay, az) < MIN_GRAVITY {
+        return calculate_heading(mx, my);
+    }
+
+    let roll = ay.atan2(az);
+    let (sin_roll, cos_roll) = roll.sin_cos();
+    let pitch = (-ax).atan2(ay * sin_roll + az * cos_roll);
+    let (sin_pitch, cos_pitch) = pitch.sin_cos();
+
+    let my_level = my * cos_roll - mz * sin_roll;
+    let mz_rolled = my * sin_roll + mz * cos_roll;
+    let mx_level = mx * cos_pitch + mz_rolled * sin_pitch;
+    calculate_heading(mx_level, my_level)
+}
+
+/// ISA barometric formula: height in meters of `pressure_hpa` above the level
+/// where the pressure is `reference_hpa`.
+pub fn barometric_altitude(pressure_hpa: f32, reference_hpa: f32) -> f32 {
+    44_330.0 * (1.0 - (pressure_hpa / reference_hpa).powf(1.0 / 5.255))
+}
+
+/// Inverse of [`barometric_altitude`]: the reference-level pressure in hPa
+/// given `pressure_hpa` measured `altitude_m` above that level.
+pub fn reference_pressure(pressure_hpa: f32, altitude_m: f32) -> f32 {
+    pressure_hpa / (1.0 - altitude_m / 44_330.0).powf(5.255)
+}
+
+/// Signed distance in meters from `point` to the great circle through
+/// `start` and `end` (each a `(lat, lon)` pair in degrees). Positive is right
+/// of the direction of travel.
+pub fn cross_track_distance(point: (f64, f64), start: (f64, f64), end: (f64, f64)) -> f64 {
+    let angular = haversine(start.0, start.1, point.0, point.1) / EARTH_RADIUS_M;
+    let to_point = bearing(start.0, start.1, point.0, point.1).to_radians();
+    let course = bearing(start.0, start.1, end.0, end.1).to_radians();
+    (angular.sin() * (to_point - course).sin()).asin() * EARTH_RADIUS_M
+}
+
+/// Point reached by travelling `distance_m` along a great circle from
+/// (`lat`, `lon`) with initial bearing `bearing_deg`, as `(lat, lon)`.
+pub fn destination(lat: f64, lon: f64, bearing_deg: f64, distance_m: f64) -> (f64, f64) {
+    let angular = distance_m / EARTH_RADIUS_M;
+    let (phi1, lambda1) = (lat.to_radians(), lon.to_radians());
+    let theta = bearing_deg.to_radians();
+    let phi2 = (phi1.sin() * angular.cos() + phi1.cos() * angular.sin() * theta.cos()).asin();
+    let lambda2 = lambda1
+        + (theta.sin() * angular.sin() * phi1.cos()).atan2(angular.cos() - phi1.sin() * phi2.sin());
+    let lon2 = (lambda2.to_degrees() + 540.0).rem_euclid(360.0) - 180.0;
+    (phi2.to_degrees(), lon2)
+}
+
+/// WGS84 semi-major axis in meters.
+pub const WGS84_A: f64 = 6_378_137.0;
+
+/// WGS84 flattening.
+pub const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// Transverse Mercator projection of a point on the WGS84 ellipsoid about
+/// `central_meridian_deg`, as `(x, y)` meters at unit scale: x east of the
+/// meridian, y north of the equator.
+///
+/// Uses Krüger's series to third order in the third flattening, accurate to
+/// well under a millimeter within a few degrees of the central meridian.
+pub fn transverse_mercator(lat: f64, lon: f64, central_meridian_deg: f64) -> (f64, f64) {
+    let n = WGS84_F / (2.0 - WGS84_F);
+    let rectifying_radius = WGS84_A / (1.0 + n) * (1.0 + n * n / 4.0 + n.powi(4) / 64.0);
+    let alpha = [
+        n / 2.0 - 2.0 * n * n / 3.0 + 5.0 * n.powi(3) / 16.0,
+        13.0 * n * n / 48.0 - 3.0 * n.powi(3) / 5.0,
+        61.0 * n.powi(3) / 240.0,
+    ];
+    let e = (WGS84_F * (2.0 - WGS84_F)).sqrt();
+
+    let (sin_phi, lambda) = (
+        lat.to_radians().sin(),
+        (lon - central_meridian_deg).to_radians(),
+    );
+    // Conformal latitude, as its tangent.
+    let t = (sin_phi.atanh() - e * (e * sin_phi).atanh()).sinh();
+    let xi = t.atan2(lambda.cos());
+    let eta = (lambda.sin() / (1.0 + t * t).sqrt()).atanh();
+
+    let (mut x, mut y) = (eta, xi);
+    for (j, a) in alpha.iter().enumerate() {
+        let k = 2.0 * (j + 1) as f64;
+        x += a * (k * xi).cos() * (k * eta).sinh();
+        y += a * (k * xi).sin() * (k * eta).cosh();
+    }
+    (rectifying_radius * x, rectifying_radius * y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_magnitude() {
+        assert_eq!(magnitude(3.0, 4.0, 0.0), 5.0);
+        assert_eq!(magnitude(0.0, 0.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_haversine() {
+        assert_eq!(haversine(37.7749, -122.4194, 37.7749, -122.4194), 0.0);
+        // One degree of latitude.
+        let degree = haversine(0.0, 0.0, 1.0, 0.0);
+        assert!((degree - 111_195.0).abs() < 1.0, "degree was {}", degree);
+    }
+
+    #[test]
+    fn test_bearing() {
+        assert!(bearing(0.0, 0.0, 1.0, 0.0).abs() < 1e-9);
+        assert!((bearing(0.0, 0.0, 0.0, 1.0) - 90.0).abs() < 1e-9);
+        assert!((bearing(0.0, 0.0, -1.0, 0.0) - 180.0).abs() < 1e-9);
+        assert!((bearing(0.0, 0.0, 0.0, -1.0) - 270.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_destination_round_trips_with_bearing_and_haversine() {
+        let (lat, lon) = destination(37.7749, -122.4194, 60.0, 5_000.0);
+        assert!((haversine(37.7749, -122.4194, lat, lon) - 5_000.0).abs() < 1e-6);
+        assert!((bearing(37.7749, -122.4194, lat, lon) - 60.0).abs() < 1e-6);
+        let (_, wrapped) = destination(0.0, 179.99, 90.0, 5_000.0);
+        assert!(wrapped < -179.0);
+    }
+
+    #[test]
+    fn test_cross_track_distance() {
+        // Due north along the prime meridian; 0.01° of longitude at the
+        // equator is about 1112 m.
+        let d = cross_track_distance((0.5, 0.01), (0.0, 0.0), (1.0, 0.0));
+        assert!((d - 1111.95).abs() < 0.5, "d {d}");
+        assert!((cross_track_distance((0.5, -0.01), (0.0, 0.0), (1.0, 0.0)) + d).abs() < 1e-6);
+        assert!(cross_track_distance((0.5, 0.0), (0.0, 0.0), (1.0, 0.0)).abs() < 1e-6);
+    }
+
+    /// Rotates a world-frame vector into the frame of a device rolled by
+    /// `roll` and pitched by `pitch` degrees.
+    fn to_device_frame(v: [f32; 3], roll: f32, pitch: f32) -> [f32; 3] {
+        let (sr, cr) = roll.to_radians().sin_cos();
+        let (sp, cp) = pitch.to_radians().sin_cos();
+        // Inverse of the pitch-then-roll leveling rotation.
+        let x = v[0] * cp - v[2] * sp;
+        let z1 = v[0] * sp + v[2] * cp;
+        let y = v[1] * cr + z1 * sr;
+        let z = -v[1] * sr + z1 * cr;
+        [x, y, z]
+    }
+
+    #[test]
+    fn test_tilt_compensated_heading_matches_flat_when_level() {
+        let heading = tilt_compensated_heading([-21.2, -21.2, -40.0], [0.0, 0.0, 9.81]);
+        assert!((heading - calculate_heading(-21.2, -21.2)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_tilt_compensated_heading_recovers_true_heading() {
+        for true_heading in [0.0_f32, 60.0, 135.0, 250.0] {
+            let h = true_heading.to_radians();
+            let field = [30.0 * h.cos(), 30.0 * h.sin(), -40.0];
+            for (roll, pitch) in [(20.0, 0.0), (0.0, -25.0), (30.0, 15.0), (-15.0, 35.0)] {
+                let mag = to_device_frame(field, roll, pitch);
+                let accel = to_device_frame([0.0, 0.0, 9.81], roll, pitch);
+
+                let heading = tilt_compensated_heading(mag, accel);
+                let error = (heading - true_heading + 540.0).rem_euclid(360.0) - 180.0;
+                assert!(
+                    error.abs() < 0.5,
+                    "heading {} vs {} at roll {} pitch {}",
+                    heading,
+                    true_heading,
+                    roll,
+                    pitch
+                );
+            }
+        }
+
+        // The flat formula is visibly wrong for the same tilted device.
+        let mag = to_device_frame([0.0, 30.0, -40.0], 30.0, 15.0);
+        assert!((calculate_heading(mag[0], mag[1]) - 90.0).abs() > 5.0);
+    }
+
+    #[test]
+    fn test_tilt_compensated_heading_free_fall_falls_back_to_flat() {
+        let heading = tilt_compensated_heading([0.0, 30.0, -40.0], [0.01, 0.0, 0.02]);
+        assert!((heading - 90.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_barometric_altitude() {
+        assert_eq!(barometric_altitude(1013.25, 1013.25), 0.0);
+        assert!((barometric_altitude(899.0, 1013.25) - 1000.0).abs() < 10.0);
+    }
+}
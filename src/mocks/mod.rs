@@ -0,0 +1,235 @@
+//! Mock sensor providers.
+//!
+//! Used when running the UI outside a device (browser, desktop) and as
+//! fixtures in tests. Every provider stamps its reading with the current time.
+
+use crate::math;
+use crate::models::{
+    calculate_heading, now_ms, AccelerometerData, GpsData, HumidityData, MagnetometerData,
+    PressureData, TemperatureData, WifiNetwork,
+};
+
+/// Standard gravity in m/s².
+pub const GRAVITY: f32 = 9.81;
+
+/// A device lying flat on a table: gravity along +Z.
+pub fn mock_accelerometer_at_rest() -> AccelerometerData {
+    AccelerometerData {
+        x: 0.0,
+        y: 0.0,
+        z: GRAVITY,
+        timestamp: now_ms(),
+        accuracy: 3,
+    }
+}
+
+/// A device being carried while walking.
+pub fn mock_accelerometer_moving() -> AccelerometerData {
+    AccelerometerData {
+        x: 1.2,
+        y: 2.5,
+        z: 10.4,
+        timestamp: now_ms(),
+        accuracy: 3,
+    }
+}
+
+/// A level device pointing at magnetic north.
+pub fn mock_magnetometer_north() -> MagnetometerData {
+    let (x, y) = (30.0, 0.0);
+    MagnetometerData {
+        x,
+        y,
+        z: -40.0,
+        heading: calculate_heading(x, y),
+        timestamp: now_ms(),
+        accuracy: 3,
+    }
+}
+
+/// A level device pointing southwest.
+pub fn mock_magnetometer_southwest() -> MagnetometerData {
+    let (x, y) = (-21.2, -21.2);
+    MagnetometerData {
+        x,
+        y,
+        z: -40.0,
+        heading: calculate_heading(x, y),
+        timestamp: now_ms(),
+        accuracy: 3,
+    }
+}
+
+/// A stationary fix in downtown San Francisco.
+pub fn mock_gps_san_francisco() -> GpsData {
+    GpsData {
+        latitude: 37.7749,
+        longitude: -122.4194,
+        altitude: Some(16.0),
+        accuracy: 5.0,
+        speed: Some(0.0),
+        bearing: None,
+        timestamp: now_ms(),
+    }
+}
+
+/// A fix taken from a moving vehicle.
+pub fn mock_gps_moving() -> GpsData {
+    GpsData {
+        latitude: 37.7849,
+        longitude: -122.4094,
+        altitude: Some(20.0),
+        accuracy: 8.0,
+        speed: Some(13.4),
+        bearing: Some(45.0),
+        timestamp: now_ms(),
+    }
+}
+
+/// Indoor room temperature.
+pub fn mock_temperature_room() -> TemperatureData {
+    TemperatureData {
+        temperature: 22.5,
+        timestamp: now_ms(),
+    }
+}
+
+/// Standard atmospheric pressure at sea level.
+pub fn mock_pressure_sea_level() -> PressureData {
+    PressureData {
+        pressure: 1013.25,
+        timestamp: now_ms(),
+    }
+}
+
+/// Indoor humidity in the comfortable band.
+pub fn mock_humidity_comfortable() -> HumidityData {
+    HumidityData {
+        humidity: 45.0,
+        timestamp: now_ms(),
+    }
+}
+
+/// A typical scan in a residential neighbourhood.
+pub fn mock_wifi_networks() -> Vec<WifiNetwork> {
+    vec![
+        WifiNetwork {
+            ssid: "HomeNetwork".to_string(),
+            bssid: "00:11:22:33:44:55".to_string(),
+            signal_strength: -45,
+            frequency: 2437,
+            security: "WPA2".to_string(),
+        },
+        WifiNetwork {
+            ssid: "Neighbor_5G".to_string(),
+            bssid: "66:77:88:99:AA:BB".to_string(),
+            signal_strength: -67,
+            frequency: 5180,
+            security: "WPA3".to_string(),
+        },
+        WifiNetwork {
+            ssid: "CoffeeShop".to_string(),
+            bssid: "CC:DD:EE:FF:00:11".to_string(),
+            signal_strength: -80,
+            frequency: 2412,
+            security: "Open".to_string(),
+        },
+    ]
+}
+
+/// A straight-line track by dead reckoning from `start`: `count` fixes
+/// `interval_ms` apart, moving at `speed_mps` along `bearing_deg`.
+///
+/// The first fix is at `start`; every fix keeps `start`'s altitude and
+/// accuracy and reports the given speed and bearing.
+pub fn generate_gps_track(
+    start: GpsData,
+    bearing_deg: f32,
+    speed_mps: f32,
+    count: usize,
+    interval_ms: i64,
+) -> Vec<GpsData> {
+    (0..count)
+        .map(|i| {
+            let elapsed_ms = interval_ms * i as i64;
+            let distance = speed_mps as f64 * elapsed_ms as f64 / 1000.0;
+            let (latitude, longitude) = math::destination(
+                start.latitude,
+                start.longitude,
+                bearing_deg as f64,
+                distance,
+            );
+            GpsData {
+                latitude,
+                longitude,
+                speed: Some(speed_mps),
+                bearing: Some(bearing_deg),
+                timestamp: start.timestamp + elapsed_ms,
+                ..start.clone()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_accelerometer_at_rest_has_gravity_on_z() {
+        let data = mock_accelerometer_at_rest();
+        assert_eq!(data.z, GRAVITY);
+        assert!(data.timestamp > 0);
+    }
+
+    #[test]
+    fn test_mock_magnetometer_headings() {
+        assert!(mock_magnetometer_north().heading.abs() < 0.01);
+        assert!((mock_magnetometer_southwest().heading - 225.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_mock_gps_fixes_are_valid() {
+        let sf = mock_gps_san_francisco();
+        assert!((-90.0..=90.0).contains(&sf.latitude));
+        assert!((-180.0..=180.0).contains(&sf.longitude));
+        assert!(mock_gps_moving().speed.unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_mock_humidity_comfortable() {
+        let data = mock_humidity_comfortable();
+        assert!((30.0..=60.0).contains(&data.humidity));
+        assert!(data.timestamp > 0);
+    }
+
+    #[test]
+    fn test_mock_wifi_networks() {
+        let networks = mock_wifi_networks();
+        assert_eq!(networks.len(), 3);
+        assert!(networks.iter().all(|n| n.signal_strength < 0));
+    }
+
+    #[test]
+    fn test_generate_gps_track() {
+        let start = mock_gps_san_francisco();
+        let track = generate_gps_track(start.clone(), 30.0, 2.5, 61, 1_000);
+        assert_eq!(track.len(), 61);
+        assert_eq!(track[0].latitude, start.latitude);
+        assert_eq!(track[60].timestamp - track[0].timestamp, 60_000);
+
+        let length: f64 = track
+            .windows(2)
+            .map(|pair| {
+                math::haversine(
+                    pair[0].latitude,
+                    pair[0].longitude,
+                    pair[1].latitude,
+                    pair[1].longitude,
+                )
+            })
+            .sum();
+        assert!((length - 2.5 * 60.0).abs() < 0.01, "length {length}");
+        assert!(generate_gps_track(start, 0.0, 1.0, 0, 1_000).is_empty());
+    }
+}
@@ -0,0 +1,307 @@
+//! Fluent builders for the reading types.
+//!
+//! Each reading type has a `builder()` entry point. Fields that are not set
+//! default to zero, optional fields to `None`, and the timestamp to the time
+//! `build()` is called. GPS fixes are the exception: a zero position or
+//! accuracy would be a plausible but wrong fix, so those must be set.
+
+use super::{
+    calculate_heading, now_ms, AccelerometerData, GpsData, GyroscopeData, HumidityData,
+    MagnetometerData, PressureData, TemperatureData,
+};
+use crate::services::SensorError;
+
+/// Defines a builder with one setter per field for a reading whose fields
+/// are all required, plus the timestamp.
+macro_rules! reading_builder {
+    ($reading:ident, $builder:ident { $($field:ident: $ty:ty),* $(,)? }) => {
+        #[doc = concat!("Fluent builder for [`", stringify!($reading), "`].")]
+        #[derive(Debug, Clone, Default)]
+        pub struct $builder {
+            $($field: $ty,)*
+            timestamp: Option<i64>,
+        }
+
+        impl $reading {
+            pub fn builder() -> $builder {
+                $builder::default()
+            }
+        }
+
+        impl $builder {
+            $(
+                pub fn $field(mut self, $field: $ty) -> Self {
+                    self.$field = $field;
+                    self
+                }
+            )*
+
+            pub fn timestamp(mut self, timestamp: i64) -> Self {
+                self.timestamp = Some(timestamp);
+                self
+            }
+
+            pub fn build(self) -> $reading {
+                $reading {
+                    $($field: self.$field,)*
+                    timestamp: self.timestamp.unwrap_or_else(now_ms),
+                }
+            }
+        }
+    };
+}
+
+reading_builder!(
+    AccelerometerData,
+    AccelerometerDataBuilder {
+        x: f32,
+        y: f32,
+        z: f32,
+        accuracy: i32,
+    }
+);
+
+reading_builder!(
+    GyroscopeData,
+    GyroscopeDataBuilder {
+        x: f32,
+        y: f32,
+        z: f32,
+        accuracy: i32,
+    }
+);
+
+reading_builder!(TemperatureData, TemperatureDataBuilder { temperature: f32 });
+
+reading_builder!(PressureData, PressureDataBuilder { pressure: f32 });
+
+reading_builder!(HumidityData, HumidityDataBuilder { humidity: f32 });
+
+/// Fluent builder for [`MagnetometerData`].
+///
+/// Unless set explicitly, `heading` is derived from the x/y components.
+#[derive(Debug, Clone, Default)]
+pub struct MagnetometerDataBuilder {
+    x: f32,
+    y: f32,
+    z: f32,
+    heading: Option<f32>,
+    accuracy: i32,
+    timestamp: Option<i64>,
+}
+
+impl MagnetometerData {
+    pub fn builder() -> MagnetometerDataBuilder {
+        MagnetometerDataBuilder::default()
+    }
+}
+
+impl MagnetometerDataBuilder {
+    pub fn x(mut self, x: f32) -> Self {
+        self.x = x;
+        self
+    }
+
+    pub fn y(mut self, y: f32) -> Self {
+        self.y = y;
+        self
+    }
+
+    pub fn z(mut self, z: f32) -> Self {
+        self.z = z;
+        self
+    }
+
+    pub fn heading(mut self, heading: f32) -> Self {
+        self.heading = Some(heading);
+        self
+    }
+
+    pub fn accuracy(mut self, accuracy: i32) -> Self {
+        self.accuracy = accuracy;
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: i64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    pub fn build(self) -> MagnetometerData {
+        MagnetometerData {
+            x: self.x,
+            y: self.y,
+            z: self.z,
+            heading: self
+                .heading
+                .unwrap_or_else(|| calculate_heading(self.x, self.y)),
+            timestamp: self.timestamp.unwrap_or_else(now_ms),
+            accuracy: self.accuracy,
+        }
+    }
+}
+
+/// Fluent builder for [`GpsData`].
+///
+/// Latitude, longitude and accuracy have no sensible default: (0, 0) is a
+/// real point in the Gulf of Guinea and an accuracy of 0 m claims a perfect
+/// fix. [`build`](Self::build) fails with `DataError` unless all three are
+/// set.
+#[derive(Debug, Clone, Default)]
+pub struct GpsDataBuilder {
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    altitude: Option<f64>,
+    accuracy: Option<f32>,
+    speed: Option<f32>,
+    bearing: Option<f32>,
+    timestamp: Option<i64>,
+}
+
+impl GpsData {
+    pub fn builder() -> GpsDataBuilder {
+        GpsDataBuilder::default()
+    }
+}
+
+impl GpsDataBuilder {
+    pub fn latitude(mut self, latitude: f64) -> Self {
+        self.latitude = Some(latitude);
+        self
+    }
+
+    pub fn longitude(mut self, longitude: f64) -> Self {
+        self.longitude = Some(longitude);
+        self
+    }
+
+    pub fn altitude(mut self, altitude: f64) -> Self {
+        self.altitude = Some(altitude);
+        self
+    }
+
+    pub fn accuracy(mut self, accuracy: f32) -> Self {
+        self.accuracy = Some(accuracy);
+        self
+    }
+
+    pub fn speed(mut self, speed: f32) -> Self {
+        self.speed = Some(speed);
+        self
+    }
+
+    pub fn bearing(mut self, bearing: f32) -> Self {
+        self.bearing = Some(bearing);
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: i64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    pub fn build(self) -> Result<GpsData, SensorError> {
+        let missing = |field: &str| SensorError::DataError(format!("GPS fix has no {field}"));
+        Ok(GpsData {
+            latitude: self.latitude.ok_or_else(|| missing("latitude"))?,
+            longitude: self.longitude.ok_or_else(|| missing("longitude"))?,
+            altitude: self.altitude,
+            accuracy: self.accuracy.ok_or_else(|| missing("accuracy"))?,
+            speed: self.speed,
+            bearing: self.bearing,
+            timestamp: self.timestamp.unwrap_or_else(now_ms),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gps_builder_defaults() {
+        let before = now_ms();
+        let fix = GpsData::builder()
+            .latitude(37.7749)
+            .longitude(-122.4194)
+            .accuracy(5.0)
+            .build()
+            .unwrap();
+        assert_eq!(fix.latitude, 37.7749);
+        assert_eq!(fix.longitude, -122.4194);
+        assert_eq!(fix.accuracy, 5.0);
+        assert_eq!(fix.altitude, None);
+        assert_eq!(fix.speed, None);
+        assert_eq!(fix.bearing, None);
+        assert!(fix.timestamp >= before && fix.timestamp <= now_ms());
+
+        let moving = GpsData::builder()
+            .latitude(0.0)
+            .longitude(0.0)
+            .accuracy(3.0)
+            .altitude(20.0)
+            .speed(13.4)
+            .bearing(45.0)
+            .timestamp(1_000)
+            .build()
+            .unwrap();
+        assert_eq!(moving.altitude, Some(20.0));
+        assert_eq!(moving.speed, Some(13.4));
+        assert_eq!(moving.bearing, Some(45.0));
+        assert_eq!(moving.timestamp, 1_000);
+    }
+
+    #[test]
+    fn test_gps_builder_requires_position_and_accuracy() {
+        let complete = || {
+            GpsData::builder()
+                .latitude(37.7749)
+                .longitude(-122.4194)
+                .accuracy(5.0)
+        };
+        assert!(complete().build().is_ok());
+
+        let partial = [
+            GpsData::builder().longitude(-122.4194).accuracy(5.0),
+            GpsData::builder().latitude(37.7749).accuracy(5.0),
+            GpsData::builder().latitude(37.7749).longitude(-122.4194),
+            GpsData::builder(),
+        ];
+        for builder in partial {
+            assert!(matches!(builder.build(), Err(SensorError::DataError(_))));
+        }
+    }
+
+    #[test]
+    fn test_reading_builders() {
+        let accel = AccelerometerData::builder()
+            .z(9.81)
+            .accuracy(3)
+            .timestamp(7)
+            .build();
+        assert_eq!(accel, AccelerometerData::from_vec3([0.0, 0.0, 9.81], 7, 3));
+
+        let mag = MagnetometerData::builder()
+            .x(-21.2)
+            .y(-21.2)
+            .z(-40.0)
+            .build();
+        assert!((mag.heading - 225.0).abs() < 0.01);
+        assert!(mag.timestamp > 0);
+        let pinned = MagnetometerData::builder().heading(10.0).build();
+        assert_eq!(pinned.heading, 10.0);
+
+        let temperature = TemperatureData::builder().temperature(22.5).build();
+        assert_eq!(temperature.temperature, 22.5);
+        assert!(temperature.timestamp > 0);
+        assert_eq!(
+            PressureData::builder().pressure(1013.25).build().pressure,
+            1013.25
+        );
+        assert_eq!(
+            HumidityData::builder().humidity(45.0).build().humidity,
+            45.0
+        );
+        assert_eq!(GyroscopeData::builder().x(0.5).build().x, 0.5);
+    }
+}
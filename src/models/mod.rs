@@ -0,0 +1,742 @@
+//! Sensor data models.
+//!
+//! Every reading carries a `timestamp` in milliseconds since the Unix epoch.
+//! Units follow the Android sensor conventions:
+//!
+//! | Sensor        | Unit                  |
+//! |---------------|-----------------------|
+//! | Accelerometer | m/s²                  |
+//! | Gyroscope     | rad/s                 |
+//! | Magnetometer  | μT, heading in degrees|
+//! | GPS           | degrees, meters, m/s  |
+//! | Temperature   | °C                    |
+//! | Pressure      | hPa                   |
+//! | Humidity      | % relative humidity   |
+//! | WiFi          | dBm, MHz              |
+
+mod builders;
+mod nmea;
+
+pub use builders::{
+    AccelerometerDataBuilder, GpsDataBuilder, GyroscopeDataBuilder, HumidityDataBuilder,
+    MagnetometerDataBuilder, PressureDataBuilder, TemperatureDataBuilder,
+};
+pub use nmea::{parse_nmea_gga, parse_nmea_gga_with_quality};
+
+pub use crate::math::calculate_heading;
+pub(crate) use crate::math::normalize_heading;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Acceleration along each device axis, including gravity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccelerometerData {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub timestamp: i64,
+    /// Android accuracy status: 0 (unreliable) to 3 (high).
+    pub accuracy: i32,
+}
+
+impl AccelerometerData {
+    pub fn from_vec3(v: [f32; 3], timestamp: i64, accuracy: i32) -> Self {
+        Self {
+            x: v[0],
+            y: v[1],
+            z: v[2],
+            timestamp,
+            accuracy,
+        }
+    }
+
+    /// The `[x, y, z]` components, for math libraries that take arrays.
+    pub fn as_vec3(&self) -> [f32; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    pub fn accuracy_level(&self) -> Accuracy {
+        Accuracy::from(self.accuracy)
+    }
+}
+
+/// Angular velocity around each device axis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GyroscopeData {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub timestamp: i64,
+    /// Android accuracy status: 0 (unreliable) to 3 (high).
+    pub accuracy: i32,
+}
+
+/// Magnetic field strength along each device axis plus the derived heading.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MagnetometerData {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    /// Heading relative to magnetic north, 0-359 degrees.
+    pub heading: f32,
+    pub timestamp: i64,
+    /// Android accuracy status: 0 (unreliable) to 3 (high).
+    pub accuracy: i32,
+}
+
+impl MagnetometerData {
+    /// Builds a reading with `heading` derived from the x/y components.
+    pub fn from_vec3(v: [f32; 3], timestamp: i64, accuracy: i32) -> Self {
+        Self {
+            x: v[0],
+            y: v[1],
+            z: v[2],
+            heading: calculate_heading(v[0], v[1]),
+            timestamp,
+            accuracy,
+        }
+    }
+
+    /// The `[x, y, z]` components, for math libraries that take arrays.
+    pub fn as_vec3(&self) -> [f32; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    pub fn accuracy_level(&self) -> Accuracy {
+        Accuracy::from(self.accuracy)
+    }
+}
+
+/// A location fix.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GpsData {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Altitude above the WGS84 ellipsoid in meters, if reported.
+    pub altitude: Option<f64>,
+    /// Horizontal accuracy radius in meters.
+    pub accuracy: f32,
+    /// Ground speed in m/s, if reported.
+    pub speed: Option<f32>,
+    /// Direction of travel (course over ground) in degrees, if reported.
+    #[serde(default)]
+    pub bearing: Option<f32>,
+    pub timestamp: i64,
+}
+
+/// Ambient temperature in degrees Celsius.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemperatureData {
+    pub temperature: f32,
+    pub timestamp: i64,
+}
+
+/// Atmospheric pressure in hectopascals.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PressureData {
+    pub pressure: f32,
+    pub timestamp: i64,
+}
+
+/// Relative humidity in percent (0-100).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HumidityData {
+    pub humidity: f32,
+    pub timestamp: i64,
+}
+
+/// A single access point from a WiFi scan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WifiNetwork {
+    pub ssid: String,
+    pub bssid: String,
+    /// Received signal strength in dBm (typically -100 to 0).
+    pub signal_strength: i32,
+    /// Channel center frequency in MHz.
+    pub frequency: u32,
+    pub security: String,
+}
+
+/// One synchronized inertial sample for orientation filters.
+///
+/// The magnetometer is optional; without it only roll and pitch are
+/// observable and yaw drifts with the gyroscope.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImuData {
+    pub accelerometer: AccelerometerData,
+    pub gyroscope: GyroscopeData,
+    pub magnetometer: Option<MagnetometerData>,
+    pub timestamp: i64,
+}
+
+/// The latest reading from each sensor at one point in time.
+///
+/// Sensors that are unavailable or have not reported yet are `None`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SensorSnapshot {
+    pub accelerometer: Option<AccelerometerData>,
+    pub magnetometer: Option<MagnetometerData>,
+    pub gps: Option<GpsData>,
+    pub temperature: Option<TemperatureData>,
+    pub pressure: Option<PressureData>,
+    /// Timestamp of the snapshot in milliseconds since the Unix epoch.
+    pub captured_at: i64,
+}
+
+impl SensorSnapshot {
+    pub fn builder() -> SensorSnapshotBuilder {
+        SensorSnapshotBuilder::default()
+    }
+}
+
+/// Fluent builder for [`SensorSnapshot`].
+///
+/// Unless set explicitly, `captured_at` is the newest timestamp among the
+/// provided readings, or 0 when there are none.
+#[derive(Debug, Clone, Default)]
+pub struct SensorSnapshotBuilder {
+    accelerometer: Option<AccelerometerData>,
+    magnetometer: Option<MagnetometerData>,
+    gps: Option<GpsData>,
+    temperature: Option<TemperatureData>,
+    pressure: Option<PressureData>,
+    captured_at: Option<i64>,
+}
+
+impl SensorSnapshotBuilder {
+    pub fn accelerometer(mut self, data: AccelerometerData) -> Self {
+        self.accelerometer = Some(data);
+        self
+    }
+
+    pub fn magnetometer(mut self, data: MagnetometerData) -> Self {
+        self.magnetometer = Some(data);
+        self
+    }
+
+    pub fn gps(mut self, data: GpsData) -> Self {
+        self.gps = Some(data);
+        self
+    }
+
+    pub fn temperature(mut self, data: TemperatureData) -> Self {
+        self.temperature = Some(data);
+        self
+    }
+
+    pub fn pressure(mut self, data: PressureData) -> Self {
+        self.pressure = Some(data);
+        self
+    }
+
+    pub fn captured_at(mut self, timestamp: i64) -> Self {
+        self.captured_at = Some(timestamp);
+        self
+    }
+
+    pub fn build(self) -> SensorSnapshot {
+        let newest = [
+            self.accelerometer.as_ref().map(|d| d.timestamp),
+            self.magnetometer.as_ref().map(|d| d.timestamp),
+            self.gps.as_ref().map(|d| d.timestamp),
+            self.temperature.as_ref().map(|d| d.timestamp),
+            self.pressure.as_ref().map(|d| d.timestamp),
+        ]
+        .into_iter()
+        .flatten()
+        .max();
+        SensorSnapshot {
+            captured_at: self.captured_at.or(newest).unwrap_or(0),
+            accelerometer: self.accelerometer,
+            magnetometer: self.magnetometer,
+            gps: self.gps,
+            temperature: self.temperature,
+            pressure: self.pressure,
+        }
+    }
+}
+
+/// Header describing a recorded [`SensorSession`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionMetadata {
+    pub device_id: String,
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    /// Number of samples per sensor, keyed by sensor name (e.g. "gps").
+    pub sample_counts: BTreeMap<String, usize>,
+}
+
+/// A complete recording: a metadata header plus every sample per sensor.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SensorSession {
+    pub metadata: SessionMetadata,
+    pub accelerometer: Vec<AccelerometerData>,
+    pub magnetometer: Vec<MagnetometerData>,
+    pub gps: Vec<GpsData>,
+    pub temperature: Vec<TemperatureData>,
+    pub pressure: Vec<PressureData>,
+}
+
+impl SensorSession {
+    pub fn new(device_id: impl Into<String>) -> Self {
+        Self {
+            metadata: SessionMetadata {
+                device_id: device_id.into(),
+                ..SessionMetadata::default()
+            },
+            ..Self::default()
+        }
+    }
+
+    /// Recomputes the header's time range and sample counts from the samples.
+    pub fn update_metadata(&mut self) {
+        self.metadata = self.computed_metadata();
+    }
+
+    /// The header as [`update_metadata`](Self::update_metadata) would set
+    /// it, without modifying the session.
+    pub fn computed_metadata(&self) -> SessionMetadata {
+        let timestamps: Vec<i64> = self
+            .accelerometer
+            .iter()
+            .map(|d| d.timestamp)
+            .chain(self.magnetometer.iter().map(|d| d.timestamp))
+            .chain(self.gps.iter().map(|d| d.timestamp))
+            .chain(self.temperature.iter().map(|d| d.timestamp))
+            .chain(self.pressure.iter().map(|d| d.timestamp))
+            .collect();
+        SessionMetadata {
+            device_id: self.metadata.device_id.clone(),
+            start_timestamp: timestamps.iter().copied().min().unwrap_or(0),
+            end_timestamp: timestamps.iter().copied().max().unwrap_or(0),
+            sample_counts: [
+                ("accelerometer", self.accelerometer.len()),
+                ("magnetometer", self.magnetometer.len()),
+                ("gps", self.gps.len()),
+                ("temperature", self.temperature.len()),
+                ("pressure", self.pressure.len()),
+            ]
+            .into_iter()
+            .map(|(name, count)| (name.to_string(), count))
+            .collect(),
+        }
+    }
+}
+
+/// A reading taken at a point in time, for generic code that sorts or
+/// windows readings from different sensors.
+pub trait SensorReading {
+    /// Milliseconds since the Unix epoch.
+    fn timestamp(&self) -> i64;
+
+    /// How old the reading is at `now` (ms since the epoch). Negative when
+    /// the reading is stamped in the future.
+    fn age_ms(&self, now: i64) -> i64 {
+        now - self.timestamp()
+    }
+}
+
+/// Former name of [`SensorReading`], kept so existing bounds still compile.
+pub use SensorReading as HasTimestamp;
+
+macro_rules! impl_sensor_reading {
+    ($($ty:ty),* $(,)?) => {
+        $(impl SensorReading for $ty {
+            fn timestamp(&self) -> i64 {
+                self.timestamp
+            }
+        })*
+    };
+}
+
+impl_sensor_reading!(
+    AccelerometerData,
+    GyroscopeData,
+    MagnetometerData,
+    GpsData,
+    TemperatureData,
+    PressureData,
+    HumidityData,
+    ImuData,
+);
+
+impl SensorReading for SensorSnapshot {
+    fn timestamp(&self) -> i64 {
+        self.captured_at
+    }
+}
+
+/// A value paired with the unit it is expressed in, for self-describing payloads.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnitAnnotated<T> {
+    pub value: T,
+    pub unit: String,
+}
+
+impl<T> UnitAnnotated<T> {
+    pub fn new(value: T, unit: impl Into<String>) -> Self {
+        Self {
+            value,
+            unit: unit.into(),
+        }
+    }
+}
+
+/// Computes a compass heading that stays correct when the device is tilted,
+/// using the accelerometer to level the magnetometer vector.
+///
+/// Falls back to [`calculate_heading`] when the accelerometer reads near zero
+/// (free fall). See [`math::tilt_compensated_heading`](crate::math::tilt_compensated_heading).
+pub fn calculate_tilt_compensated_heading(
+    mag: &MagnetometerData,
+    accel: &AccelerometerData,
+) -> f32 {
+    crate::math::tilt_compensated_heading([mag.x, mag.y, mag.z], [accel.x, accel.y, accel.z])
+}
+
+/// Converts a magnetic heading to a true-north heading.
+///
+/// `declination_degrees` is positive when magnetic north lies east of true
+/// north and negative when west. The result is normalized to 0-359 degrees,
+/// whatever the size of the declination.
+pub fn apply_declination(magnetic_heading: f32, declination_degrees: f32) -> f32 {
+    normalize_heading(magnetic_heading + declination_degrees)
+}
+
+/// Maps a heading in degrees to one of the eight cardinal/intercardinal points.
+pub fn get_cardinal_direction(heading: f32) -> &'static str {
+    const DIRECTIONS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    let index = ((normalize_heading(heading) + 22.5) / 45.0) as usize % 8;
+    DIRECTIONS[index]
+}
+
+/// The kinds of sensor this crate models.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SensorType {
+    Accelerometer,
+    Gyroscope,
+    Magnetometer,
+    Gps,
+    Temperature,
+    Pressure,
+    Humidity,
+    Wifi,
+}
+
+/// A device axis, in the Android sensor coordinate system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// How [`SensorService::direction_name`](crate::SensorService::direction_name)
+/// spells a compass direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DirectionStyle {
+    /// Compass abbreviation, e.g. "NE".
+    Abbreviated,
+    /// Full English name, e.g. "Northeast".
+    Full,
+}
+
+/// Android sensor accuracy status, the typed form of the `accuracy: i32`
+/// fields. Serializes as the plain number so existing JSON is unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(from = "i32", into = "i32")]
+pub enum Accuracy {
+    Unreliable,
+    Low,
+    Medium,
+    High,
+}
+
+impl From<i32> for Accuracy {
+    /// Values below 0 read as `Unreliable` and above 3 as `High`.
+    fn from(value: i32) -> Self {
+        match value {
+            i32::MIN..=0 => Accuracy::Unreliable,
+            1 => Accuracy::Low,
+            2 => Accuracy::Medium,
+            _ => Accuracy::High,
+        }
+    }
+}
+
+impl From<Accuracy> for i32 {
+    fn from(accuracy: Accuracy) -> Self {
+        accuracy as i32
+    }
+}
+
+impl std::fmt::Display for Accuracy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Accuracy::Unreliable => "Unreliable",
+            Accuracy::Low => "Low",
+            Accuracy::Medium => "Medium",
+            Accuracy::High => "High",
+        })
+    }
+}
+
+/// The current time in milliseconds since the Unix epoch.
+pub(crate) fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Signed shortest rotation in degrees from heading `from` to heading `to`,
+/// in (-180, 180]. Positive is clockwise.
+pub fn heading_difference(from: f32, to: f32) -> f32 {
+    let diff = normalize_heading(to - from);
+    if diff > 180.0 {
+        diff - 360.0
+    } else {
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accelerometer_serialization() {
+        let data = AccelerometerData {
+            x: 0.1,
+            y: 9.81,
+            z: 0.2,
+            timestamp: 1_700_000_000_000,
+            accuracy: 3,
+        };
+        let json = serde_json::to_string(&data).unwrap();
+        let parsed: AccelerometerData = serde_json::from_str(&json).unwrap();
+        assert_eq!(data, parsed);
+    }
+
+    #[test]
+    fn test_gps_serialization_with_missing_fields() {
+        let data = GpsData {
+            latitude: 37.7749,
+            longitude: -122.4194,
+            altitude: None,
+            accuracy: 5.0,
+            speed: None,
+            bearing: None,
+            timestamp: 1_700_000_000_000,
+        };
+        let json = serde_json::to_string(&data).unwrap();
+        let parsed: GpsData = serde_json::from_str(&json).unwrap();
+        assert_eq!(data, parsed);
+    }
+
+    #[test]
+    fn test_gps_deserializes_without_bearing() {
+        let json = r#"{"latitude":1.0,"longitude":2.0,"altitude":null,"accuracy":3.0,"speed":null,"timestamp":0}"#;
+        let parsed: GpsData = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.bearing, None);
+    }
+
+    #[test]
+    fn test_humidity_serialization() {
+        let data = HumidityData {
+            humidity: 45.0,
+            timestamp: 1_700_000_000_000,
+        };
+        let json = serde_json::to_string(&data).unwrap();
+        assert_eq!(json, r#"{"humidity":45.0,"timestamp":1700000000000}"#);
+        let parsed: HumidityData = serde_json::from_str(&json).unwrap();
+        assert_eq!(data, parsed);
+    }
+
+    #[test]
+    fn test_wifi_network_serialization() {
+        let network = WifiNetwork {
+            ssid: "HomeNetwork".to_string(),
+            bssid: "00:11:22:33:44:55".to_string(),
+            signal_strength: -45,
+            frequency: 2437,
+            security: "WPA2".to_string(),
+        };
+        let json = serde_json::to_string(&network).unwrap();
+        let parsed: WifiNetwork = serde_json::from_str(&json).unwrap();
+        assert_eq!(network, parsed);
+    }
+
+    #[test]
+    fn test_snapshot_builder_derives_captured_at() {
+        let snapshot = SensorSnapshot::builder()
+            .temperature(TemperatureData {
+                temperature: 21.0,
+                timestamp: 1_000,
+            })
+            .pressure(PressureData {
+                pressure: 1013.0,
+                timestamp: 3_000,
+            })
+            .build();
+
+        assert_eq!(snapshot.captured_at, 3_000);
+        assert!(snapshot.accelerometer.is_none());
+        assert!(snapshot.gps.is_none());
+        assert_eq!(snapshot.temperature.unwrap().temperature, 21.0);
+
+        let explicit = SensorSnapshot::builder().captured_at(42).build();
+        assert_eq!(explicit.captured_at, 42);
+        assert_eq!(SensorSnapshot::builder().build().captured_at, 0);
+    }
+
+    #[test]
+    fn test_session_update_metadata() {
+        let mut session = SensorSession::new("pixel-7");
+        session.temperature.push(TemperatureData {
+            temperature: 20.0,
+            timestamp: 5_000,
+        });
+        session.pressure.push(PressureData {
+            pressure: 1000.0,
+            timestamp: 2_000,
+        });
+        session.update_metadata();
+
+        assert_eq!(session.metadata.device_id, "pixel-7");
+        assert_eq!(session.metadata.start_timestamp, 2_000);
+        assert_eq!(session.metadata.end_timestamp, 5_000);
+        assert_eq!(session.metadata.sample_counts["pressure"], 1);
+        assert_eq!(session.metadata.sample_counts["gps"], 0);
+    }
+
+    #[test]
+    fn test_calculate_heading() {
+        assert!((calculate_heading(1.0, 0.0) - 0.0).abs() < 0.01);
+        assert!((calculate_heading(0.0, 1.0) - 90.0).abs() < 0.01);
+        assert!((calculate_heading(-1.0, 0.0) - 180.0).abs() < 0.01);
+        assert!((calculate_heading(0.0, -1.0) - 270.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_heading_difference_wraps() {
+        assert_eq!(heading_difference(10.0, 30.0), 20.0);
+        assert_eq!(heading_difference(350.0, 10.0), 20.0);
+        assert_eq!(heading_difference(10.0, 350.0), -20.0);
+        assert_eq!(heading_difference(0.0, 180.0), 180.0);
+    }
+
+    #[test]
+    fn test_tilt_compensated_heading_on_level_device() {
+        let mag = MagnetometerData {
+            x: 0.0,
+            y: 30.0,
+            z: -40.0,
+            heading: 90.0,
+            timestamp: 0,
+            accuracy: 3,
+        };
+        let accel = AccelerometerData {
+            x: 0.0,
+            y: 0.0,
+            z: 9.81,
+            timestamp: 0,
+            accuracy: 3,
+        };
+        let heading = calculate_tilt_compensated_heading(&mag, &accel);
+        assert!((heading - 90.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_apply_declination() {
+        assert_eq!(apply_declination(10.0, -13.0), 357.0);
+        assert_eq!(apply_declination(350.0, 15.0), 5.0);
+        assert_eq!(apply_declination(90.0, 0.0), 90.0);
+        assert_eq!(apply_declination(90.0, 725.0), 95.0);
+        assert_eq!(apply_declination(90.0, -450.0), 0.0);
+    }
+
+    #[test]
+    fn test_get_cardinal_direction() {
+        assert_eq!(get_cardinal_direction(0.0), "N");
+        assert_eq!(get_cardinal_direction(45.0), "NE");
+        assert_eq!(get_cardinal_direction(90.0), "E");
+        assert_eq!(get_cardinal_direction(225.0), "SW");
+        assert_eq!(get_cardinal_direction(350.0), "N");
+        assert_eq!(get_cardinal_direction(-90.0), "W");
+    }
+
+    #[test]
+    fn test_vec3_round_trip() {
+        let accel = AccelerometerData::from_vec3([0.1, -0.2, 9.81], 42, 3);
+        assert_eq!(accel.as_vec3(), [0.1, -0.2, 9.81]);
+        assert_eq!(
+            AccelerometerData::from_vec3(accel.as_vec3(), accel.timestamp, accel.accuracy),
+            accel
+        );
+
+        let mag = MagnetometerData::from_vec3([-21.2, -21.2, -40.0], 42, 2);
+        assert_eq!(mag.as_vec3(), [-21.2, -21.2, -40.0]);
+        assert!((mag.heading - 225.0).abs() < 0.01);
+        assert_eq!(
+            MagnetometerData::from_vec3(mag.as_vec3(), mag.timestamp, mag.accuracy),
+            mag
+        );
+    }
+
+    #[test]
+    fn test_sensor_reading_trait() {
+        fn newest<T: SensorReading>(readings: &[T]) -> Option<i64> {
+            readings.iter().map(SensorReading::timestamp).max()
+        }
+
+        let accel = AccelerometerData::from_vec3([0.0, 0.0, 9.81], 1_000, 3);
+        let pressure = PressureData {
+            pressure: 1013.25,
+            timestamp: 1_500,
+        };
+        let readings: Vec<&dyn SensorReading> = vec![&accel, &pressure];
+        let ages: Vec<i64> = readings.iter().map(|r| r.age_ms(2_000)).collect();
+        assert_eq!(ages, vec![1_000, 500]);
+        assert_eq!(pressure.age_ms(1_000), -500);
+
+        let snapshot = SensorSnapshot::builder().captured_at(7).build();
+        assert_eq!(snapshot.timestamp(), 7);
+        assert_eq!(newest(&[accel.clone(), accel]), Some(1_000));
+    }
+
+    #[test]
+    fn test_accuracy_conversions() {
+        assert_eq!(Accuracy::from(0), Accuracy::Unreliable);
+        assert_eq!(Accuracy::from(1), Accuracy::Low);
+        assert_eq!(Accuracy::from(2), Accuracy::Medium);
+        assert_eq!(Accuracy::from(3), Accuracy::High);
+        assert_eq!(Accuracy::from(-1), Accuracy::Unreliable);
+        assert_eq!(Accuracy::from(7), Accuracy::High);
+        for level in 0..=3 {
+            assert_eq!(i32::from(Accuracy::from(level)), level);
+        }
+        assert_eq!(Accuracy::Medium.to_string(), "Medium");
+
+        let accel = AccelerometerData::from_vec3([0.0, 0.0, 9.81], 0, 2);
+        assert_eq!(accel.accuracy_level(), Accuracy::Medium);
+        let mag = MagnetometerData::from_vec3([30.0, 0.0, -40.0], 0, 3);
+        assert_eq!(mag.accuracy_level(), Accuracy::High);
+    }
+
+    #[test]
+    fn test_accuracy_serializes_as_number() {
+        assert_eq!(serde_json::to_string(&Accuracy::High).unwrap(), "3");
+        let parsed: Accuracy = serde_json::from_str("1").unwrap();
+        assert_eq!(parsed, Accuracy::Low);
+
+        // Readings keep their existing numeric JSON.
+        let accel = AccelerometerData::from_vec3([0.0, 0.0, 9.81], 5, 3);
+        let json: serde_json::Value = serde_json::to_value(&accel).unwrap();
+        assert_eq!(json["accuracy"], 3);
+        let level: Accuracy = serde_json::from_value(json["accuracy"].clone()).unwrap();
+        assert_eq!(level, accel.accuracy_level());
+    }
+}
@@ -0,0 +1,236 @@
+//! Parsing for NMEA 0183 sentences from external GPS modules.
+
+use super::GpsData;
+use crate::services::SensorError;
+
+/// Rough user-equivalent range error in meters, used to turn HDOP into an
+/// accuracy radius.
+const UERE_M: f32 = 5.0;
+
+/// Parses a GGA (fix data) sentence such as
+/// `$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47`.
+///
+/// Any talker ID is accepted (`GP`, `GN`, ...). The checksum is required.
+///
+/// GGA carries less than [`GpsData`] expects:
+/// - `timestamp` is milliseconds since UTC midnight; the sentence has no date.
+/// - `accuracy` is estimated as HDOP × 5 m.
+/// - `altitude` is the mean-sea-level altitude plus the geoid separation,
+///   i.e. height above the WGS84 ellipsoid.
+/// - `speed` and `bearing` are `None`.
+///
+/// A fix quality of 0 (no fix) is rejected with a `DataError`; use
+/// [`parse_nmea_gga_with_quality`] to also get the quality indicator.
+pub fn parse_nmea_gga(sentence: &str) -> Result<GpsData, SensorError> {
+    parse_nmea_gga_with_quality(sentence).map(|(fix, _)| fix)
+}
+
+/// [`parse_nmea_gga`] that also returns the GGA fix quality indicator
+/// (1 = GPS, 2 = DGPS, 4 = RTK fixed, 5 = RTK float, ...), which has no
+/// place in [`GpsData`].
+pub fn parse_nmea_gga_with_quality(sentence: &str) -> Result<(GpsData, u8), SensorError> {
+    let body = verify_checksum(sentence.trim())?;
+    let fields: Vec<&str> = body.split(',').collect();
+    if fields.len() < 12 || fields[0].len() != 5 || !fields[0].ends_with("GGA") {
+        return Err(malformed("not a GGA sentence"));
+    }
+
+    let quality: u8 = fields[6].parse().map_err(|_| malformed("fix quality"))?;
+    if quality == 0 {
+        return Err(SensorError::DataError(
+            "GGA sentence has no fix".to_string(),
+        ));
+    }
+
+    let latitude = coordinate(fields[2], fields[3], 2, 90.0, 'N', 'S')?;
+    let longitude = coordinate(fields[4], fields[5], 3, 180.0, 'E', 'W')?;
+    let hdop: f32 = fields[8]
+        .parse()
+        .ok()
+        .filter(|h: &f32| h.is_finite() && *h >= 0.0)
+        .ok_or_else(|| malformed("HDOP"))?;
+    let altitude = match (fields[9], fields[11]) {
+        ("", _) => None,
+        (msl, separation) => {
+            let msl = finite_number(msl, "altitude")?;
+            let separation = if separation.is_empty() {
+                0.0
+            } else {
+                finite_number(separation, "geoid separation")?
+            };
+            Some(msl + separation)
+        }
+    };
+
+    let fix = GpsData {
+        latitude,
+        longitude,
+        altitude,
+        accuracy: hdop * UERE_M,
+        speed: None,
+        bearing: None,
+        timestamp: time_of_day_ms(fields[1])?,
+    };
+    Ok((fix, quality))
+}
+
+/// Returns the text between `$` and `*` if the trailing checksum matches.
+fn verify_checksum(sentence: &str) -> Result<&str, SensorError> {
+    let (body, checksum) = sentence
+        .strip_prefix('$')
+        .and_then(|rest| rest.split_once('*'))
+        .ok_or_else(|| malformed("expected $...*hh framing"))?;
+    let expected = u8::from_str_radix(checksum, 16).map_err(|_| malformed("checksum"))?;
+    let actual = body.bytes().fold(0u8, |acc, b| acc ^ b);
+    if actual != expected {
+        return Err(SensorError::DataError(format!(
+            "NMEA checksum mismatch: expected {expected:02X}, computed {actual:02X}"
+        )));
+    }
+    Ok(body)
+}
+
+/// Converts `ddmm.mmmm` (or `dddmm.mmmm`) plus a hemisphere into signed
+/// decimal degrees no larger than `max_degrees` in magnitude.
+fn coordinate(
+    value: &str,
+    hemisphere: &str,
+    degree_digits: usize,
+    max_degrees: f64,
+    positive: char,
+    negative: char,
+) -> Result<f64, SensorError> {
+    // Whole degrees and minutes must be digits, which also rules out signs,
+    // `nan` and `inf`.
+    if value.len() < degree_digits + 2
+        || !value.as_bytes()[..degree_digits + 2]
+            .iter()
+            .all(u8::is_ascii_digit)
+    {
+        return Err(malformed("coordinate"));
+    }
+    let (degrees, minutes) = value.split_at(degree_digits);
+    let degrees: f64 = degrees.parse().map_err(|_| malformed("coordinate"))?;
+    let minutes = finite_number(minutes, "coordinate")?;
+    if minutes >= 60.0 {
+        return Err(malformed("coordinate minutes"));
+    }
+    let magnitude = degrees + minutes / 60.0;
+    if magnitude > max_degrees {
+        return Err(malformed("coordinate out of range"));
+    }
+    match hemisphere.chars().next() {
+        Some(c) if c == positive && hemisphere.len() == 1 => Ok(magnitude),
+        Some(c) if c == negative && hemisphere.len() == 1 => Ok(-magnitude),
+        _ => Err(malformed("hemisphere")),
+    }
+}
+
+/// Converts `hhmmss[.ss]` to milliseconds since midnight.
+fn time_of_day_ms(value: &str) -> Result<i64, SensorError> {
+    if value.len() < 6 || !value.as_bytes()[..6].iter().all(u8::is_ascii_digit) {
+        return Err(malformed("time"));
+    }
+    let (hms, fraction) = value.split_at(6);
+    let hours: i64 = hms[0..2].parse().map_err(|_| malformed("time"))?;
+    let minutes: i64 = hms[2..4].parse().map_err(|_| malformed("time"))?;
+    let seconds: i64 = hms[4..6].parse().map_err(|_| malformed("time"))?;
+    let millis = if fraction.is_empty() {
+        0
+    } else {
+        let fraction: f64 = fraction.parse().map_err(|_| malformed("time"))?;
+        (fraction * 1000.0).round() as i64
+    };
+    Ok(((hours * 60 + minutes) * 60 + seconds) * 1000 + millis)
+}
+
+fn finite_number(value: &str, what: &str) -> Result<f64, SensorError> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or_else(|| malformed(what))
+}
+
+fn malformed(what: &str) -> SensorError {
+    SensorError::DataError(format!("malformed NMEA sentence: {what}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+
+    #[test]
+    fn test_parse_nmea_gga() {
+        let (fix, quality) = parse_nmea_gga_with_quality(SAMPLE).unwrap();
+        assert_eq!(quality, 1);
+        assert_eq!(parse_nmea_gga(SAMPLE).unwrap(), fix);
+        assert!((fix.latitude - 48.1173).abs() < 1e-9);
+        assert!((fix.longitude - 11.516_666_666).abs() < 1e-6);
+        assert!((fix.altitude.unwrap() - 592.3).abs() < 1e-9);
+        assert!((fix.accuracy - 4.5).abs() < 1e-6);
+        assert_eq!(fix.speed, None);
+        assert_eq!(fix.timestamp, (12 * 3600 + 35 * 60 + 19) * 1000);
+    }
+
+    #[test]
+    fn test_parse_nmea_gga_southern_western_hemispheres() {
+        let body = "GNGGA,002153.50,3342.6618,S,15111.1234,W,2,10,1.2,,M,,M,,";
+        let checksum = body.bytes().fold(0u8, |acc, b| acc ^ b);
+        let fix = parse_nmea_gga(&format!("${body}*{checksum:02X}")).unwrap();
+        assert!(fix.latitude < 0.0 && fix.longitude < 0.0);
+        assert_eq!(fix.altitude, None);
+        assert_eq!(fix.timestamp, (21 * 60 + 53) * 1000 + 500);
+    }
+
+    #[test]
+    fn test_parse_nmea_gga_rejects_bad_input() {
+        let corrupted = SAMPLE.replace("*47", "*48");
+        assert!(matches!(
+            parse_nmea_gga(&corrupted),
+            Err(SensorError::DataError(_))
+        ));
+        assert!(parse_nmea_gga("GPGGA,123519").is_err());
+
+        let no_fix = "GPGGA,123519,4807.038,N,01131.000,E,0,00,,,M,,M,,";
+        let checksum = no_fix.bytes().fold(0u8, |acc, b| acc ^ b);
+        assert!(parse_nmea_gga(&format!("${no_fix}*{checksum:02X}")).is_err());
+    }
+
+    fn with_checksum(body: &str) -> String {
+        let checksum = body.bytes().fold(0u8, |acc, b| acc ^ b);
+        format!("${body}*{checksum:02X}")
+    }
+
+    #[test]
+    fn test_parse_nmea_gga_rejects_out_of_range_fields() {
+        for (latitude, longitude, hdop) in [
+            ("9900.000", "01131.000", "0.9"), // 99° latitude
+            ("4807.038", "18100.000", "0.9"), // 181° longitude
+            ("48-5.000", "01131.000", "0.9"), // negative minutes
+            ("48nan", "01131.000", "0.9"),
+            ("4807.038", "011inf", "0.9"),
+            ("4807.038", "01131.000", "NaN"),
+            ("4807.038", "01131.000", "inf"),
+            ("4807.038", "01131.000", "-1.0"),
+        ] {
+            let sentence = with_checksum(&format!(
+                "GPGGA,123519,{latitude},N,{longitude},E,1,08,{hdop},545.4,M,46.9,M,,"
+            ));
+            assert!(
+                matches!(parse_nmea_gga(&sentence), Err(SensorError::DataError(_))),
+                "accepted {sentence}"
+            );
+        }
+        let nan_altitude = with_checksum("GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,nan,M,,M,,");
+        assert!(parse_nmea_gga(&nan_altitude).is_err());
+
+        let (_, quality) = parse_nmea_gga_with_quality(&with_checksum(
+            "GNGGA,123519,9000.000,S,18000.000,W,4,12,0.5,10.0,M,,M,,",
+        ))
+        .unwrap();
+        assert_eq!(quality, 4);
+    }
+}
@@ -0,0 +1,610 @@
+//! Noise filters for sensor streams.
+//!
+//! Each filter keeps its state between calls, so a long stream can be fed in
+//! batches and the output matches filtering it in one go.
+
+use super::{from_local_xy, local_xy};
+use crate::math;
+use crate::models::{AccelerometerData, GpsData};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
+
+/// A smoothing strategy over a stream of scalar samples.
+pub trait NoiseFilter {
+    /// Filters a batch of samples, returning one output per input.
+    fn filter(&mut self, samples: &[f32]) -> Vec<f32>;
+}
+
+/// Mean of the last `window` samples.
+#[derive(Debug, Clone)]
+pub struct MovingAverageFilter {
+    window: usize,
+    buffer: VecDeque<f32>,
+}
+
+impl MovingAverageFilter {
+    /// A window of 0 is treated as 1 (pass-through).
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            buffer: VecDeque::new(),
+        }
+    }
+}
+
+impl NoiseFilter for MovingAverageFilter {
+    fn filter(&mut self, samples: &[f32]) -> Vec<f32> {
+        samples
+            .iter()
+            .map(|&sample| {
+                if self.buffer.len() == self.window {
+                    self.buffer.pop_front();
+                }
+                self.buffer.push_back(sample);
+                self.buffer.iter().sum::<f32>() / self.buffer.len() as f32
+            })
+            .collect()
+    }
+}
+
+/// Exponential moving average: `out = alpha * new + (1 - alpha) * prev`.
+#[derive(Debug, Clone)]
+pub struct EmaFilter {
+    alpha: f32,
+    last: Option<f32>,
+}
+
+impl EmaFilter {
+    /// `alpha` is clamped to 0.0-1.0; higher values follow the input more closely.
+    pub fn new(alpha: f32) -> Self {
+        Self {
+            alpha: alpha.clamp(0.0, 1.0),
+            last: None,
+        }
+    }
+}
+
+impl NoiseFilter for EmaFilter {
+    fn filter(&mut self, samples: &[f32]) -> Vec<f32> {
+        samples
+            .iter()
+            .map(|&sample| {
+                let out = match self.last {
+                    Some(prev) => self.alpha * sample + (1.0 - self.alpha) * prev,
+                    None => sample,
+                };
+                self.last = Some(out);
+                out
+            })
+            .collect()
+    }
+}
+
+/// Per-axis exponential smoothing of an accelerometer stream:
+/// `out = alpha * new + (1 - alpha) * prev`.
+#[derive(Debug, Clone)]
+pub struct LowPassFilter {
+    alpha: f32,
+    last: Option<AccelerometerData>,
+}
+
+impl LowPassFilter {
+    /// `alpha` is clamped to 0.0-1.0; higher values follow the input more closely.
+    pub fn new(alpha: f32) -> Self {
+        Self {
+            alpha: alpha.clamp(0.0, 1.0),
+            last: None,
+        }
+    }
+
+    /// Smooths one sample. The first sample passes through unchanged; the
+    /// output keeps the incoming sample's timestamp and accuracy.
+    pub fn filter(&mut self, data: &AccelerometerData) -> AccelerometerData {
+        let out = match &self.last {
+            Some(prev) => {
+                let smooth = |new: f32, old: f32| self.alpha * new + (1.0 - self.alpha) * old;
+                AccelerometerData {
+                    x: smooth(data.x, prev.x),
+                    y: smooth(data.y, prev.y),
+                    z: smooth(data.z, prev.z),
+                    ..data.clone()
+                }
+            }
+            None => data.clone(),
+        };
+        self.last = Some(out.clone());
+        out
+    }
+}
+
+/// Median of the last `window` samples; removes isolated spikes entirely.
+#[derive(Debug, Clone)]
+pub struct MedianFilter {
+    window: usize,
+    buffer: VecDeque<f32>,
+}
+
+impl MedianFilter {
+    /// A window of 0 is treated as 1 (pass-through).
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            buffer: VecDeque::new(),
+        }
+    }
+}
+
+impl NoiseFilter for MedianFilter {
+    fn filter(&mut self, samples: &[f32]) -> Vec<f32> {
+        samples
+            .iter()
+            .map(|&sample| {
+                if self.buffer.len() == self.window {
+                    self.buffer.pop_front();
+                }
+                self.buffer.push_back(sample);
+                let mut sorted: Vec<f32> = self.buffer.iter().copied().collect();
+                sorted.sort_by(f32::total_cmp);
+                let mid = sorted.len() / 2;
+                if sorted.len().is_multiple_of(2) {
+                    (sorted[mid - 1] + sorted[mid]) / 2.0
+                } else {
+                    sorted[mid]
+                }
+            })
+            .collect()
+    }
+}
+
+/// Running median over every value pushed so far, in O(log n) per push.
+///
+/// Keeps the lower half of the values in a max-heap and the upper half in a
+/// min-heap, with the lower half holding at most one extra value.
+#[derive(Debug, Clone, Default)]
+pub struct StreamingMedian {
+    lower: BinaryHeap<OrderedF32>,
+    upper: BinaryHeap<Reverse<OrderedF32>>,
+}
+
+impl StreamingMedian {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, value: f32) {
+        match self.lower.peek() {
+            Some(top) if value > top.0 => self.upper.push(Reverse(OrderedF32(value))),
+            _ => self.lower.push(OrderedF32(value)),
+        }
+        if self.lower.len() > self.upper.len() + 1 {
+            if let Some(moved) = self.lower.pop() {
+                self.upper.push(Reverse(moved));
+            }
+        } else if self.upper.len() > self.lower.len() {
+            if let Some(Reverse(moved)) = self.upper.pop() {
+                self.lower.push(moved);
+            }
+        }
+    }
+
+    /// The median of all pushed values, or `None` before the first push.
+    pub fn median(&self) -> Option<f32> {
+        let low = self.lower.peek()?.0;
+        if self.lower.len() > self.upper.len() {
+            Some(low)
+        } else {
+            self.upper.peek().map(|Reverse(high)| (low + high.0) / 2.0)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lower.len() + self.upper.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lower.is_empty()
+    }
+}
+
+/// Constant-velocity Kalman filter that smooths a stream of GPS fixes.
+///
+/// Positions are tracked in meters on a local plane anchored at the first fix,
+/// with east and north filtered independently. Each fix's `accuracy` (in
+/// meters) sets how much it is trusted, so poor fixes move the estimate less.
+#[derive(Debug, Clone)]
+pub struct GpsKalmanFilter {
+    process_noise: f64,
+    measurement_noise: f64,
+    origin: Option<GpsData>,
+    east: AxisState,
+    north: AxisState,
+    last_timestamp: i64,
+}
+
+/// Position/velocity estimate and covariance along one axis.
+#[derive(Debug, Clone, Copy, Default)]
+struct AxisState {
+    position: f64,
+    velocity: f64,
+    p: [[f64; 2]; 2],
+}
+
+impl AxisState {
+    fn start(position: f64, variance: f64) -> Self {
+        Self {
+            position,
+            velocity: 0.0,
+            // Velocity is unknown at the first fix; start it very uncertain.
+            p: [[variance, 0.0], [0.0, 1.0e4]],
+        }
+    }
+
+    fn predict(&mut self, dt: f64, q: f64) {
+        self.position += self.velocity * dt;
+        let [[p00, p01], [p10, p11]] = self.p;
+        let (dt2, dt3, dt4) = (dt * dt, dt * dt * dt, dt * dt * dt * dt);
+        self.p = [
+            [
+                p00 + dt * (p10 + p01) + dt2 * p11 + q * dt4 / 4.0,
+                p01 + dt * p11 + q * dt3 / 2.0,
+            ],
+            [p10 + dt * p11 + q * dt3 / 2.0, p11 + q * dt2],
+        ];
+    }
+
+    fn correct(&mut self, measured: f64, r: f64) {
+        let [[p00, p01], [p10, p11]] = self.p;
+        let s = p00 + r;
+        let (k0, k1) = (p00 / s, p10 / s);
+        let innovation = measured - self.position;
+        self.position += k0 * innovation;
+        self.velocity += k1 * innovation;
+        self.p = [
+            [(1.0 - k0) * p00, (1.0 - k0) * p01],
+            [p10 - k1 * p00, p11 - k1 * p01],
+        ];
+    }
+}
+
+impl GpsKalmanFilter {
+    /// `process_noise` is the expected acceleration variance in (m/s²)²;
+    /// `measurement_noise` is a variance in m² added to each fix's
+    /// `accuracy²`.
+    pub fn new(process_noise: f64, measurement_noise: f64) -> Self {
+        Self {
+            process_noise: process_noise.max(0.0),
+            measurement_noise: measurement_noise.max(0.0),
+            origin: None,
+            east: AxisState::default(),
+            north: AxisState::default(),
+            last_timestamp: 0,
+        }
+    }
+
+    /// Feeds a fix and returns it with latitude/longitude replaced by the
+    /// smoothed estimate and `accuracy` by the estimate's standard deviation.
+    pub fn update(&mut self, fix: &GpsData) -> GpsData {
+        let r = self.measurement_noise + (fix.accuracy as f64).powi(2);
+        let Some(origin) = &self.origin else {
+            self.origin = Some(fix.clone());
+            self.east = AxisState::start(0.0, r);
+            self.north = AxisState::start(0.0, r);
+            self.last_timestamp = fix.timestamp;
+            return fix.clone();
+        };
+
+        let (east, north) = local_xy(origin, fix);
+        let dt = ((fix.timestamp - self.last_timestamp).max(0) as f64) / 1000.0;
+        self.last_timestamp = fix.timestamp;
+        for (axis, measured) in [(&mut self.east, east), (&mut self.north, north)] {
+            axis.predict(dt, self.process_noise);
+            axis.correct(measured, r);
+        }
+
+        let (latitude, longitude) = from_local_xy(origin, self.east.position, self.north.position);
+        let variance = self.east.p[0][0].max(self.north.p[0][0]);
+        GpsData {
+            latitude,
+            longitude,
+            accuracy: variance.sqrt() as f32,
+            ..fix.clone()
+        }
+    }
+}
+
+/// A configurable stage that drops implausible accelerometer samples.
+///
+/// Rules are added with the builder methods and every enabled rule must pass
+/// for a sample to be accepted. Rate and z-score checks compare against
+/// previously *accepted* samples only, so a rejected spike never becomes the
+/// baseline for the next sample.
+///
+/// ```
+/// use mobile_poc::OutlierRejector;
+///
+/// let rejector = OutlierRejector::new()
+///     .max_magnitude(80.0)
+///     .max_rate_of_change(500.0)
+///     .z_score(4.0, 20);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OutlierRejector {
+    max_magnitude: Option<f32>,
+    max_rate_of_change: Option<f32>,
+    z_score: Option<(f32, usize)>,
+    last_accepted: Option<AccelerometerData>,
+    recent_magnitudes: VecDeque<f32>,
+}
+
+impl OutlierRejector {
+    /// A rejector with no rules, which accepts every sample.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rejects samples whose acceleration magnitude exceeds `limit` m/s².
+    pub fn max_magnitude(mut self, limit: f32) -> Self {
+        self.max_magnitude = Some(limit);
+        self
+    }
+
+    /// Rejects samples that change faster than `limit` m/s² per second
+    /// relative to the last accepted sample.
+    pub fn max_rate_of_change(mut self, limit: f32) -> Self {
+        self.max_rate_of_change = Some(limit);
+        self
+    }
+
+    /// Rejects samples whose magnitude z-score against the last `window`
+    /// accepted magnitudes exceeds `threshold`. The check is skipped until
+    /// three samples have been accepted and while the window has no variance.
+    pub fn z_score(mut self, threshold: f32, window: usize) -> Self {
+        self.z_score = Some((threshold, window.max(3)));
+        self
+    }
+
+    /// Returns the sample if every rule accepts it, `None` otherwise.
+    pub fn process(&mut self, sample: &AccelerometerData) -> Option<AccelerometerData> {
+        let magnitude = math::magnitude(sample.x, sample.y, sample.z);
+        if !magnitude.is_finite() {
+            return None;
+        }
+        if self.max_magnitude.is_some_and(|limit| magnitude > limit) {
+            return None;
+        }
+        if let (Some(limit), Some(last)) = (self.max_rate_of_change, &self.last_accepted) {
+            let dt = (sample.timestamp - last.timestamp) as f32 / 1000.0;
+            let change = math::magnitude(sample.x - last.x, sample.y - last.y, sample.z - last.z);
+            if dt > 0.0 && change / dt > limit {
+                return None;
+            }
+        }
+        if let Some((threshold, window)) = self.z_score {
+            let n = self.recent_magnitudes.len();
+            if n >= 3 {
+                let mean = self.recent_magnitudes.iter().sum::<f32>() / n as f32;
+                let variance = self
+                    .recent_magnitudes
+                    .iter()
+                    .map(|m| (m - mean).powi(2))
+                    .sum::<f32>()
+                    / n as f32;
+                let std_dev = variance.sqrt();
+                if std_dev > 1e-6 && ((magnitude - mean) / std_dev).abs() > threshold {
+                    return None;
+                }
+            }
+            if n == window {
+                self.recent_magnitudes.pop_front();
+            }
+            self.recent_magnitudes.push_back(magnitude);
+        }
+
+        self.last_accepted = Some(sample.clone());
+        Some(sample.clone())
+    }
+}
+
+/// `f32` with a total order so it can live in a heap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OrderedF32(f32);
+
+impl Eq for OrderedF32 {}
+
+impl PartialOrd for OrderedF32 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrderedF32 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filters_through_trait_object() {
+        let input = [1.0, 1.0, 10.0, 1.0, 1.0];
+        let mut filters: Vec<Box<dyn NoiseFilter>> = vec![
+            Box::new(MovingAverageFilter::new(3)),
+            Box::new(MedianFilter::new(3)),
+        ];
+
+        let outputs: Vec<Vec<f32>> = filters.iter_mut().map(|f| f.filter(&input)).collect();
+
+        assert_eq!(outputs[0].len(), input.len());
+        assert!((outputs[0][2] - 4.0).abs() < 1e-6);
+        assert_eq!(outputs[1], vec![1.0, 1.0, 1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_ema_filter_carries_state_between_batches() {
+        let mut whole = EmaFilter::new(0.5);
+        let expected = whole.filter(&[0.0, 10.0, 10.0, 10.0]);
+
+        let mut batched = EmaFilter::new(0.5);
+        let mut actual = batched.filter(&[0.0, 10.0]);
+        actual.extend(batched.filter(&[10.0, 10.0]));
+
+        assert_eq!(actual, expected);
+        assert_eq!(expected, vec![0.0, 5.0, 7.5, 8.75]);
+    }
+
+    #[test]
+    fn test_zero_window_passes_through() {
+        let mut filter = MovingAverageFilter::new(0);
+        assert_eq!(filter.filter(&[3.0, 5.0]), vec![3.0, 5.0]);
+    }
+
+    #[test]
+    fn test_streaming_median_matches_brute_force() {
+        // Small LCG so the test is deterministic without a rand dependency.
+        let mut seed: u32 = 12345;
+        let mut next = || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 8) as f32 / (1 << 24) as f32 * 20.0 - 10.0
+        };
+
+        let mut median = StreamingMedian::new();
+        assert_eq!(median.median(), None);
+
+        let mut seen = Vec::new();
+        for _ in 0..500 {
+            let value = next();
+            median.push(value);
+            seen.push(value);
+
+            let mut sorted = seen.clone();
+            sorted.sort_by(f32::total_cmp);
+            let mid = sorted.len() / 2;
+            let expected = if sorted.len().is_multiple_of(2) {
+                (sorted[mid - 1] + sorted[mid]) / 2.0
+            } else {
+                sorted[mid]
+            };
+            assert_eq!(median.median(), Some(expected));
+        }
+        assert_eq!(median.len(), 500);
+    }
+
+    #[test]
+    fn test_gps_kalman_filter_smooths_straight_track() {
+        use crate::mocks::mock_gps_san_francisco;
+
+        let origin = mock_gps_san_francisco();
+        let mut filter = GpsKalmanFilter::new(0.1, 0.0);
+        let (mut raw_error, mut smoothed_error) = (0.0, 0.0);
+
+        // Walking north at 5 m/s with deterministic ±10 m east/west noise.
+        for i in 0..60 {
+            let noise = if i % 3 == 0 { 10.0 } else { -5.0 };
+            let (latitude, longitude) = from_local_xy(&origin, noise, 5.0 * i as f64);
+            let fix = GpsData {
+                latitude,
+                longitude,
+                accuracy: 10.0,
+                timestamp: origin.timestamp + i * 1000,
+                ..origin.clone()
+            };
+            let smoothed = filter.update(&fix);
+            if i >= 30 {
+                raw_error += local_xy(&origin, &fix).0.abs();
+                smoothed_error += local_xy(&origin, &smoothed).0.abs();
+            }
+        }
+
+        assert!(
+            smoothed_error < raw_error / 2.0,
+            "smoothed {} vs raw {}",
+            smoothed_error,
+            raw_error
+        );
+    }
+
+    #[test]
+    fn test_outlier_rejector_drops_spike() {
+        let mut rejector = OutlierRejector::new()
+            .max_magnitude(80.0)
+            .max_rate_of_change(500.0)
+            .z_score(4.0, 20);
+        let sample = |i: i64, z: f32| AccelerometerData {
+            x: 0.0,
+            y: 0.0,
+            z,
+            timestamp: i * 20,
+            accuracy: 3,
+        };
+
+        for i in 0..10 {
+            let z = 9.81 + if i % 2 == 0 { 0.1 } else { -0.1 };
+            assert!(rejector.process(&sample(i, z)).is_some());
+        }
+        // A 30 m/s² jump within 20 ms: too fast and far outside the window.
+        assert!(rejector.process(&sample(10, 40.0)).is_none());
+        assert!(rejector.process(&sample(11, 9.85)).is_some());
+        assert!(rejector.process(&sample(12, 100.0)).is_none());
+        assert!(rejector.process(&sample(13, f32::NAN)).is_none());
+    }
+
+    #[test]
+    fn test_outlier_rejector_without_rules_accepts_everything() {
+        let mut rejector = OutlierRejector::new();
+        let spike = AccelerometerData {
+            x: 500.0,
+            y: 0.0,
+            z: 0.0,
+            timestamp: 0,
+            accuracy: 3,
+        };
+        assert_eq!(rejector.process(&spike), Some(spike));
+    }
+
+    #[test]
+    fn test_low_pass_filter_converges_on_step() {
+        let mut filter = LowPassFilter::new(0.5);
+        let sample = |z: f32, timestamp: i64, accuracy: i32| AccelerometerData {
+            x: 0.0,
+            y: 0.0,
+            z,
+            timestamp,
+            accuracy,
+        };
+
+        assert_eq!(filter.filter(&sample(0.0, 0, 3)).z, 0.0);
+        let mut last = 0.0;
+        for i in 1..=10 {
+            let out = filter.filter(&sample(10.0, i * 20, 2));
+            assert!(out.z > last && out.z < 10.0);
+            assert_eq!(out.timestamp, i * 20);
+            assert_eq!(out.accuracy, 2);
+            last = out.z;
+        }
+        assert!(10.0 - last < 0.01, "after ten samples: {}", last);
+    }
+
+    #[test]
+    fn test_low_pass_filter_clamps_alpha() {
+        let mut filter = LowPassFilter::new(2.0);
+        let first = AccelerometerData {
+            x: 1.0,
+            y: 2.0,
+            z: 3.0,
+            timestamp: 0,
+            accuracy: 3,
+        };
+        let second = AccelerometerData {
+            x: 4.0,
+            y: 5.0,
+            z: 6.0,
+            timestamp: 20,
+            accuracy: 3,
+        };
+        filter.filter(&first);
+        assert_eq!(filter.filter(&second), second);
+    }
+}
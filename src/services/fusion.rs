@@ -0,0 +1,204 @@
+//! Orientation estimation from fused inertial sensors.
+
+use crate::models::ImuData;
+
+/// Mahony complementary filter for device orientation.
+///
+/// Integrates the gyroscope and corrects its drift with proportional-integral
+/// feedback on the error between the measured and predicted gravity (and,
+/// when present, magnetic field) directions. Cheaper than a Madgwick filter
+/// and tuned with two gains: `kp` sets how fast the estimate follows the
+/// accelerometer/magnetometer, `ki` how fast gyro bias is learned (0
+/// disables bias correction).
+///
+/// The orientation is a unit quaternion `[w, x, y, z]` rotating the device
+/// frame into the earth frame, starting at the identity.
+#[derive(Debug, Clone)]
+pub struct MahonyFilter {
+    sample_period_s: f32,
+    kp: f32,
+    ki: f32,
+    quaternion: [f32; 4],
+    integral_feedback: [f32; 3],
+}
+
+impl MahonyFilter {
+    /// A non-positive `sample_rate_hz` is treated as 1 Hz.
+    pub fn new(sample_rate_hz: f32, kp: f32, ki: f32) -> Self {
+        let sample_rate_hz = if sample_rate_hz > 0.0 {
+            sample_rate_hz
+        } else {
+            1.0
+        };
+        Self {
+            sample_period_s: 1.0 / sample_rate_hz,
+            kp,
+            ki,
+            quaternion: [1.0, 0.0, 0.0, 0.0],
+            integral_feedback: [0.0; 3],
+        }
+    }
+
+    /// The current orientation estimate as `[w, x, y, z]`.
+    pub fn quaternion(&self) -> [f32; 4] {
+        self.quaternion
+    }
+
+    /// Feeds one sample and returns the updated orientation `[w, x, y, z]`.
+    ///
+    /// Gyroscope rates are in rad/s. A zero accelerometer vector skips the
+    /// feedback step, as does a zero magnetometer vector for the yaw terms.
+    pub fn update(&mut self, imu: &ImuData) -> [f32; 4] {
+        let [q0, q1, q2, q3] = self.quaternion;
+        let mut gyro = [imu.gyroscope.x, imu.gyroscope.y, imu.gyroscope.z];
+
+        if let Some([ax, ay, az]) = normalized(imu.accelerometer.as_vec3()) {
+            // Gravity direction predicted by the current estimate (halved).
+            let half_v = [
+                q1 * q3 - q0 * q2,
+                q0 * q1 + q2 * q3,
+                q0 * q0 - 0.5 + q3 * q3,
+            ];
+            let mut half_e = cross([ax, ay, az], half_v);
+
+            if let Some([mx, my, mz]) = imu
+                .magnetometer
+                .as_ref()
+                .and_then(|m| normalized(m.as_vec3()))
+            {
+                // Earth-frame field, flattened onto the x/z plane so that only
+                // its heading (not dip) feeds back.
+                let hx = 2.0
+                    * (mx * (0.5 - q2 * q2 - q3 * q3)
+                        + my * (q1 * q2 - q0 * q3)
+                        + mz * (q1 * q3 + q0 * q2));
+                let hy = 2.0
+                    * (mx * (q1 * q2 + q0 * q3)
+                        + my * (0.5 - q1 * q1 - q3 * q3)
+                        + mz * (q2 * q3 - q0 * q1));
+                let bx = hx.hypot(hy);
+                let bz = 2.0
+                    * (mx * (q1 * q3 - q0 * q2)
+                        + my * (q2 * q3 + q0 * q1)
+                        + mz * (0.5 - q1 * q1 - q2 * q2));
+                let half_w = [
+                    bx * (0.5 - q2 * q2 - q3 * q3) + bz * (q1 * q3 - q0 * q2),
+                    bx * (q1 * q2 - q0 * q3) + bz * (q0 * q1 + q2 * q3),
+                    bx * (q0 * q2 + q1 * q3) + bz * (0.5 - q1 * q1 - q2 * q2),
+                ];
+                let mag_error = cross([mx, my, mz], half_w);
+                for (e, m) in half_e.iter_mut().zip(mag_error) {
+                    *e += m;
+                }
+            }
+
+            for axis in 0..3 {
+                if self.ki > 0.0 {
+                    self.integral_feedback[axis] +=
+                        2.0 * self.ki * half_e[axis] * self.sample_period_s;
+                    gyro[axis] += self.integral_feedback[axis];
+                } else {
+                    self.integral_feedback[axis] = 0.0;
+                }
+                gyro[axis] += 2.0 * self.kp * half_e[axis];
+            }
+        }
+
+        let [gx, gy, gz] = gyro.map(|g| g * 0.5 * self.sample_period_s);
+        let next = [
+            q0 - q1 * gx - q2 * gy - q3 * gz,
+            q1 + q0 * gx + q2 * gz - q3 * gy,
+            q2 + q0 * gy - q1 * gz + q3 * gx,
+            q3 + q0 * gz + q1 * gy - q2 * gx,
+        ];
+        let norm = next.iter().map(|c| c * c).sum::<f32>().sqrt();
+        if norm > f32::EPSILON {
+            self.quaternion = next.map(|c| c / norm);
+        }
+        self.quaternion
+    }
+}
+
+fn normalized(v: [f32; 3]) -> Option<[f32; 3]> {
+    let norm = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    (norm > f32::EPSILON).then(|| v.map(|c| c / norm))
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks::*;
+    use crate::models::{AccelerometerData, GyroscopeData};
+
+    fn still_gyro() -> GyroscopeData {
+        GyroscopeData {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            timestamp: 0,
+            accuracy: 3,
+        }
+    }
+
+    fn unit_norm(q: [f32; 4]) -> bool {
+        (q.iter().map(|c| c * c).sum::<f32>().sqrt() - 1.0).abs() < 1e-4
+    }
+
+    #[test]
+    fn test_mahony_stays_stable_at_rest() {
+        let mut filter = MahonyFilter::new(100.0, 1.0, 0.1);
+        let imu = ImuData {
+            accelerometer: mock_accelerometer_at_rest(),
+            gyroscope: still_gyro(),
+            magnetometer: Some(mock_magnetometer_north()),
+            timestamp: 0,
+        };
+        for _ in 0..1_000 {
+            let q = filter.update(&imu);
+            assert!(unit_norm(q));
+            assert!((q[0] - 1.0).abs() < 1e-4, "drifted to {q:?}");
+        }
+    }
+
+    #[test]
+    fn test_mahony_converges_to_tilt() {
+        // Rolled 90° onto its side: gravity along +y in the device frame.
+        let mut filter = MahonyFilter::new(100.0, 2.0, 0.0);
+        let imu = ImuData {
+            accelerometer: AccelerometerData {
+                x: 0.0,
+                y: GRAVITY,
+                z: 0.0,
+                timestamp: 0,
+                accuracy: 3,
+            },
+            gyroscope: still_gyro(),
+            magnetometer: None,
+            timestamp: 0,
+        };
+        let mut q = filter.quaternion();
+        for _ in 0..3_000 {
+            q = filter.update(&imu);
+        }
+        assert!(unit_norm(q));
+        let [q0, q1, q2, q3] = q;
+        // Earth's vertical expressed in the device frame.
+        let up = [
+            2.0 * (q1 * q3 - q0 * q2),
+            2.0 * (q0 * q1 + q2 * q3),
+            q0 * q0 - q1 * q1 - q2 * q2 + q3 * q3,
+        ];
+        assert!(up[0].abs() < 0.01 && (up[1] - 1.0).abs() < 0.01 && up[2].abs() < 0.01);
+
+        let settled = filter.update(&imu);
+        assert!(settled.iter().zip(q).all(|(a, b)| (a - b).abs() < 1e-4));
+    }
+}
@@ -0,0 +1,4867 @@
+//! Sensor services: validation, formatting and derived calculations.
+
+pub mod filters;
+pub mod fusion;
+#[cfg(feature = "async")]
+pub mod player;
+pub mod sink;
+
+pub use crate::math::EARTH_RADIUS_M;
+
+/// 0 °C expressed in Kelvin.
+const ZERO_CELSIUS_IN_KELVIN: f32 = 273.15;
+
+/// Derived metrics reported by [`SensorService::available_metrics`], grouped
+/// by the sensor they are derived from. Each name is the method that computes
+/// it.
+///
+/// A metric computes a value from readings: a statistic, a derived quantity
+/// or a conversion into other units or coordinates. Methods that validate,
+/// format, serialize or reshape data (filtering, segmenting, simplifying,
+/// resampling, fixed-point encoding) are not metrics.
+const METRICS: &[&str] = &[
+    // Accelerometer
+    "calculate_acceleration_magnitude",
+    "planar_magnitude",
+    "bandpass_magnitude",
+    "cadence",
+    "recommend_sample_rate",
+    "activity_counts",
+    "orientation_angles",
+    "is_free_fall",
+    "vibration_dose_value",
+    "zero_crossing_rate",
+    "extract_features",
+    "detect_orientation",
+    "estimate_step_length",
+    // Accelerometer + gyroscope
+    "is_steady_for_capture",
+    // Magnetometer
+    "heading_drift_rate",
+    "fuse_heading",
+    "heading_to_grid",
+    // GPS
+    "distance_between",
+    "bearing_between",
+    "relative_bearing",
+    "eta_seconds",
+    "grade_percent",
+    "speed_from_fixes",
+    "speed_kmh",
+    "speed_mph",
+    "relative_velocity",
+    "accuracy_bounds",
+    "bounding_circle",
+    "to_enu",
+    "cross_track_distance",
+    "track_distance_filtered",
+    "elevation_profile",
+    "route_summary",
+    "grid_convergence",
+    "gps_to_utm",
+    "gps_to_geohash",
+    // Pressure
+    "altitude_qnh",
+    "height_qfe",
+    "station_to_qnh",
+    "temperature_compensate_pressure",
+    "pressure_in_inhg",
+    "pressure_in_mmhg",
+    "pressure_in_psi",
+    // Temperature and humidity
+    "to_fahrenheit",
+    "to_kelvin",
+    "to_celsius_from_kelvin",
+    "dew_point",
+    "heat_index",
+    "comfort_alerts",
+    // WiFi
+    "scan_stability",
+    "wifi_channel",
+    "wifi_band",
+    "classify_band",
+    "is_wifi6e",
+    "estimate_wifi_distance",
+    "fingerprint_position",
+    "trilaterate",
+    // Any sampled series
+    "detect_anomalies",
+];
+
+use crate::math;
+use crate::models::{
+    apply_declination, get_cardinal_direction, heading_difference, normalize_heading,
+    AccelerometerData, Axis, DirectionStyle, GpsData, GyroscopeData, HumidityData,
+    MagnetometerData, PressureData, SensorReading, SensorSession, TemperatureData, UnitAnnotated,
+    WifiNetwork,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
+use thiserror::Error;
+
+pub use filters::{
+    EmaFilter, GpsKalmanFilter, LowPassFilter, MedianFilter, MovingAverageFilter, NoiseFilter,
+    OutlierRejector, StreamingMedian,
+};
+pub use fusion::MahonyFilter;
+#[cfg(feature = "async")]
+pub use player::SessionPlayer;
+pub use sink::{SampleSink, VecSink, WriterSink};
+
+/// Errors surfaced by sensor access and processing.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum SensorError {
+    #[error("Sensor not available: {0}")]
+    NotAvailable(String),
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+    #[error("Hardware error: {0}")]
+    HardwareError(String),
+    #[error("Data error: {0}")]
+    DataError(String),
+    #[error("Plugin error: {0}")]
+    PluginError(String),
+    #[error("Empty input: {0}")]
+    EmptyInput(String),
+}
+
+impl SensorError {
+    /// Whether the failure is likely transient, so the operation is worth retrying.
+    ///
+    /// Hardware and plugin glitches often clear up; a missing sensor, a denied
+    /// permission or bad data will fail the same way again.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            SensorError::HardwareError(_) | SensorError::PluginError(_)
+        )
+    }
+}
+
+/// Stateless helper that validates, formats and derives values from readings.
+#[derive(Debug, Clone, Default)]
+pub struct SensorService;
+
+impl SensorService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Magnitude of the acceleration vector in m/s².
+    pub fn calculate_acceleration_magnitude(&self, data: &AccelerometerData) -> f32 {
+        math::magnitude(data.x, data.y, data.z)
+    }
+
+    pub fn validate_accelerometer(&self, data: &AccelerometerData) -> Result<(), SensorError> {
+        if !(data.x.is_finite() && data.y.is_finite() && data.z.is_finite()) {
+            return Err(SensorError::DataError(
+                "Accelerometer values must be finite".to_string(),
+            ));
+        }
+        if !(0..=3).contains(&data.accuracy) {
+            return Err(SensorError::DataError(format!(
+                "Invalid accuracy level: {}",
+                data.accuracy
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn validate_gps(&self, data: &GpsData) -> Result<(), SensorError> {
+        if !(-90.0..=90.0).contains(&data.latitude) {
+            return Err(SensorError::DataError(format!(
+                "Invalid latitude: {}",
+                data.latitude
+            )));
+        }
+        if !(-180.0..=180.0).contains(&data.longitude) {
+            return Err(SensorError::DataError(format!(
+                "Invalid longitude: {}",
+                data.longitude
+            )));
+        }
+        if data.accuracy < 0.0 {
+            return Err(SensorError::DataError(format!(
+                "Invalid accuracy: {}",
+                data.accuracy
+            )));
+        }
+        Ok(())
+    }
+
+    /// Accepts the operating range of typical phone temperature sensors, -40 to 85°C.
+    pub fn validate_temperature(&self, data: &TemperatureData) -> Result<(), SensorError> {
+        self.validate_temperature_range(data, -40.0, 85.0)
+    }
+
+    /// Validates a temperature against custom bounds, both inclusive and in °C.
+    pub fn validate_temperature_range(
+        &self,
+        data: &TemperatureData,
+        min_c: f32,
+        max_c: f32,
+    ) -> Result<(), SensorError> {
+        if !(min_c..=max_c).contains(&data.temperature) {
+            return Err(SensorError::DataError(format!(
+                "Temperature out of range: {}°C (expected {}°C to {}°C)",
+                data.temperature, min_c, max_c
+            )));
+        }
+        Ok(())
+    }
+
+    /// Accepts 300-1100 hPa, covering everything from high altitude to deep valleys.
+    pub fn validate_pressure(&self, data: &PressureData) -> Result<(), SensorError> {
+        if !(300.0..=1100.0).contains(&data.pressure) {
+            return Err(SensorError::DataError(format!(
+                "Pressure out of range: {} hPa",
+                data.pressure
+            )));
+        }
+        Ok(())
+    }
+
+    /// Accepts relative humidity from 0 to 100%.
+    pub fn validate_humidity(&self, data: &HumidityData) -> Result<(), SensorError> {
+        if !(0.0..=100.0).contains(&data.humidity) {
+            return Err(SensorError::DataError(format!(
+                "Humidity out of range: {}%",
+                data.humidity
+            )));
+        }
+        Ok(())
+    }
+
+    /// Accepts field magnitudes of 0-1000 μT (Earth's field is 25-65 μT; the
+    /// rest tolerates nearby metal) and headings of 0-360°.
+    pub fn validate_magnetometer(&self, data: &MagnetometerData) -> Result<(), SensorError> {
+        let magnitude = math::magnitude(data.x, data.y, data.z);
+        if !(0.0..=1000.0).contains(&magnitude) {
+            return Err(SensorError::DataError(format!(
+                "Magnetic field magnitude out of range: {magnitude} μT"
+            )));
+        }
+        if !(0.0..=360.0).contains(&data.heading) {
+            return Err(SensorError::DataError(format!(
+                "Heading out of range: {}°",
+                data.heading
+            )));
+        }
+        Ok(())
+    }
+
+    /// Rejects negative timestamps and timestamps more than 24 hours after
+    /// `now` (both ms since the epoch), which point to corrupt plugin data.
+    /// Pair it with the per-sensor validators, passing the reading's
+    /// timestamp.
+    pub fn validate_timestamp(&self, ts: i64, now: i64) -> Result<(), SensorError> {
+        const MAX_FUTURE_MS: i64 = 24 * 60 * 60 * 1000;
+        if ts < 0 {
+            return Err(SensorError::DataError(format!(
+                "Timestamp is negative: {ts}"
+            )));
+        }
+        if ts > now.saturating_add(MAX_FUTURE_MS) {
+            return Err(SensorError::DataError(format!(
+                "Timestamp is more than 24 hours in the future: {ts} (now {now})"
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn format_accelerometer(&self, data: &AccelerometerData) -> String {
+        format!(
+            "X: {:.2}, Y: {:.2}, Z: {:.2} m/s² (|a| = {:.2})",
+            data.x,
+            data.y,
+            data.z,
+            self.calculate_acceleration_magnitude(data)
+        )
+    }
+
+    pub fn format_heading(&self, data: &MagnetometerData) -> String {
+        format!(
+            "{:.0}° {}",
+            data.heading,
+            get_cardinal_direction(data.heading)
+        )
+    }
+
+    /// Formats the heading corrected to true north, e.g. `"357° N"`.
+    /// See [`apply_declination`] for the sign convention.
+    pub fn format_true_heading(&self, data: &MagnetometerData, declination: f32) -> String {
+        let heading = apply_declination(data.heading, declination);
+        format!("{:.0}° {}", heading, get_cardinal_direction(heading))
+    }
+
+    pub fn format_gps(&self, data: &GpsData) -> String {
+        format!(
+            "{:.6}, {:.6} (±{:.1}m)",
+            data.latitude, data.longitude, data.accuracy
+        )
+    }
+
+    pub fn format_temperature(&self, data: &TemperatureData) -> String {
+        let fahrenheit = self.to_fahrenheit(data.temperature);
+        format!("{:.1}°C ({:.1}°F)", data.temperature, fahrenheit)
+    }
+
+    /// Like [`format_temperature`](Self::format_temperature) with Kelvin
+    /// added, e.g. `"22.5°C (72.5°F, 295.65 K)"`.
+    pub fn format_temperature_full(&self, data: &TemperatureData) -> Result<String, SensorError> {
+        let kelvin = self.to_kelvin(data.temperature)?;
+        Ok(format!(
+            "{:.1}°C ({:.1}°F, {:.2} K)",
+            data.temperature,
+            self.to_fahrenheit(data.temperature),
+            kelvin
+        ))
+    }
+
+    pub fn to_fahrenheit(&self, celsius: f32) -> f32 {
+        celsius * 9.0 / 5.0 + 32.0
+    }
+
+    /// Converts Celsius to Kelvin, rejecting temperatures below absolute zero.
+    pub fn to_kelvin(&self, celsius: f32) -> Result<f32, SensorError> {
+        let kelvin = celsius + ZERO_CELSIUS_IN_KELVIN;
+        if kelvin < 0.0 {
+            return Err(SensorError::DataError(format!(
+                "{celsius}°C is below absolute zero"
+            )));
+        }
+        Ok(kelvin)
+    }
+
+    /// Converts Kelvin to Celsius, rejecting negative Kelvin.
+    pub fn to_celsius_from_kelvin(&self, kelvin: f32) -> Result<f32, SensorError> {
+        if kelvin < 0.0 {
+            return Err(SensorError::DataError(format!(
+                "{kelvin} K is below absolute zero"
+            )));
+        }
+        Ok(kelvin - ZERO_CELSIUS_IN_KELVIN)
+    }
+
+    pub fn format_pressure(&self, data: &PressureData) -> String {
+        format!("{:.2} hPa", data.pressure)
+    }
+
+    /// Formats pressure in hPa and inches of mercury, e.g.
+    /// `"1013.25 hPa (29.92 inHg)"`.
+    pub fn format_pressure_multi(&self, data: &PressureData) -> String {
+        format!(
+            "{:.2} hPa ({:.2} inHg)",
+            data.pressure,
+            self.pressure_in_inhg(data)
+        )
+    }
+
+    pub fn pressure_in_inhg(&self, data: &PressureData) -> f32 {
+        data.pressure * 0.029_53
+    }
+
+    pub fn pressure_in_mmhg(&self, data: &PressureData) -> f32 {
+        data.pressure * 0.750_06
+    }
+
+    pub fn pressure_in_psi(&self, data: &PressureData) -> f32 {
+        data.pressure * 0.014_504
+    }
+
+    /// Formats humidity with a comfort band: Dry (<30%), Comfortable (30-60%)
+    /// or Humid (>60%), e.g. `"45.0% (Comfortable)"`.
+    pub fn format_humidity(&self, data: &HumidityData) -> String {
+        let band = if data.humidity < 30.0 {
+            "Dry"
+        } else if data.humidity <= 60.0 {
+            "Comfortable"
+        } else {
+            "Humid"
+        };
+        format!("{:.1}% ({})", data.humidity, band)
+    }
+
+    pub fn format_wifi_network(&self, network: &WifiNetwork) -> String {
+        format!(
+            "{} ({} dBm, {} MHz, {}, {})",
+            network.ssid,
+            network.signal_strength,
+            network.frequency,
+            self.wifi_band(network),
+            network.security
+        )
+    }
+
+    /// Serializes any reading to a JSON string.
+    pub fn to_json<T: Serialize>(&self, data: &T) -> Result<String, SensorError> {
+        serde_json::to_string(data).map_err(|e| SensorError::DataError(e.to_string()))
+    }
+
+    /// Serializes an accelerometer reading with the unit of each axis embedded,
+    /// e.g. `{"x": {"value": 0.0, "unit": "m/s²"}, ...}`.
+    pub fn to_json_with_units(&self, data: &AccelerometerData) -> Value {
+        const UNIT: &str = "m/s²";
+        json!({
+            "x": UnitAnnotated::new(data.x, UNIT),
+            "y": UnitAnnotated::new(data.y, UNIT),
+            "z": UnitAnnotated::new(data.z, UNIT),
+            "timestamp": UnitAnnotated::new(data.timestamp, "ms"),
+            "accuracy": data.accuracy,
+        })
+    }
+
+    /// Dominant cadence of a walking/running signal in steps per minute.
+    ///
+    /// Autocorrelates the mean-removed acceleration magnitude and picks the lag
+    /// with the strongest correlation between 0.25 s and 2 s (30-240 steps/min).
+    /// Returns 0.0 when the signal is flat or shows no clear periodicity.
+    /// Fails with `EmptyInput` for fewer than 4 samples, too few to find any
+    /// period, and with `DataError` for a non-positive sample rate.
+    pub fn cadence(
+        &self,
+        samples: &[AccelerometerData],
+        sample_rate_hz: f32,
+    ) -> Result<f32, SensorError> {
+        const MIN_CORRELATION: f32 = 0.3;
+        if samples.len() < 4 {
+            return Err(SensorError::EmptyInput(
+                "cadence needs at least 4 accelerometer samples".to_string(),
+            ));
+        }
+        if sample_rate_hz <= 0.0 {
+            return Err(SensorError::DataError(format!(
+                "sample rate must be positive, got {sample_rate_hz} Hz"
+            )));
+        }
+
+        let magnitudes: Vec<f32> = samples
+            .iter()
+            .map(|s| self.calculate_acceleration_magnitude(s))
+            .collect();
+        let (mean, std_dev) = mean_std(&magnitudes);
+        let centered: Vec<f32> = magnitudes.iter().map(|m| m - mean).collect();
+        let energy = std_dev.powi(2) * centered.len() as f32;
+        if energy < 1e-6 {
+            return Ok(0.0);
+        }
+
+        let min_lag = ((sample_rate_hz * 0.25).round() as usize).max(1);
+        let max_lag = ((sample_rate_hz * 2.0).round() as usize).min(centered.len() / 2);
+        let mut best: Option<(usize, f32)> = None;
+        for lag in min_lag..=max_lag {
+            let correlation: f32 = centered
+                .iter()
+                .zip(&centered[lag..])
+                .map(|(a, b)| a * b)
+                .sum::<f32>()
+                / energy;
+            if best.is_none_or(|(_, c)| correlation > c) {
+                best = Some((lag, correlation));
+            }
+        }
+
+        Ok(match best {
+            Some((lag, correlation)) if correlation >= MIN_CORRELATION => {
+                60.0 * sample_rate_hz / lag as f32
+            }
+            _ => 0.0,
+        })
+    }
+
+    /// Returns a copy of the fix with longitude wrapped into [-180, 180] and
+    /// latitude clamped to [-90, 90]. Already-valid coordinates are untouched.
+    pub fn normalize_gps(&self, data: &GpsData) -> GpsData {
+        let longitude = if (-180.0..=180.0).contains(&data.longitude) {
+            data.longitude
+        } else {
+            (data.longitude + 180.0).rem_euclid(360.0) - 180.0
+        };
+        GpsData {
+            latitude: data.latitude.clamp(-90.0, 90.0),
+            longitude,
+            ..data.clone()
+        }
+    }
+
+    /// Suggests a polling rate between `min_hz` (device still) and `max_hz`
+    /// (high motion) from the variance of recent acceleration magnitudes.
+    ///
+    /// The rate rises exponentially towards `max_hz` as variance grows, so a
+    /// few m/s² of jitter is enough to sample at nearly full speed. Fails with
+    /// `EmptyInput` for fewer than two samples, which have no variance.
+    pub fn recommend_sample_rate(
+        &self,
+        recent: &[AccelerometerData],
+        min_hz: f32,
+        max_hz: f32,
+    ) -> Result<f32, SensorError> {
+        /// Variance (in (m/s²)²) at which ~63% of the extra rate is applied.
+        const MOTION_VARIANCE_SCALE: f32 = 0.5;
+        let (low, high) = if min_hz <= max_hz {
+            (min_hz, max_hz)
+        } else {
+            (max_hz, min_hz)
+        };
+        if recent.len() < 2 {
+            return Err(SensorError::EmptyInput(
+                "sample rate recommendation needs at least two samples".to_string(),
+            ));
+        }
+
+        let magnitudes: Vec<f32> = recent
+            .iter()
+            .map(|s| self.calculate_acceleration_magnitude(s))
+            .collect();
+        let variance = mean_std(&magnitudes).1.powi(2);
+
+        let motion = 1.0 - (-variance / MOTION_VARIANCE_SCALE).exp();
+        Ok(low + (high - low) * motion)
+    }
+
+    /// Band-passes the acceleration magnitude to isolate step-frequency energy.
+    ///
+    /// Two exponential low-pass filters run over the magnitude: a fast one
+    /// (`high_alpha`) that removes jitter above the band and a slow one
+    /// (`low_alpha`) that tracks gravity and drift below it. Their difference
+    /// keeps the band in between; at 50 Hz, `high_alpha = 0.3` and
+    /// `low_alpha = 0.05` pass roughly 1-3 Hz. Both filters start at the first
+    /// sample so the output begins at zero instead of ringing.
+    pub fn bandpass_magnitude(
+        &self,
+        samples: &[AccelerometerData],
+        low_alpha: f32,
+        high_alpha: f32,
+    ) -> Vec<(i64, f32)> {
+        let low_alpha = low_alpha.clamp(0.0, 1.0);
+        let high_alpha = high_alpha.clamp(0.0, 1.0);
+        let Some(first) = samples.first() else {
+            return Vec::new();
+        };
+
+        let initial = self.calculate_acceleration_magnitude(first);
+        let (mut fast, mut slow) = (initial, initial);
+        samples
+            .iter()
+            .map(|sample| {
+                let magnitude = self.calculate_acceleration_magnitude(sample);
+                fast += high_alpha * (magnitude - fast);
+                slow += low_alpha * (magnitude - slow);
+                (sample.timestamp, fast - slow)
+            })
+            .collect()
+    }
+
+    /// Bounding box `(min_lat, max_lat, min_lon, max_lon)` of the accuracy
+    /// circle around a fix.
+    ///
+    /// Longitude degrees shrink with `cos(latitude)`, so the box widens in
+    /// degrees towards the poles. When the circle reaches a pole, or is wider
+    /// than the whole globe, the longitudes are `(-180, 180)`.
+    ///
+    /// Longitudes are wrapped into [-180, 180]; `min_lon > max_lon` means the
+    /// box crosses the antimeridian, e.g. `(179.9, -179.9)`.
+    pub fn accuracy_bounds(&self, data: &GpsData) -> (f64, f64, f64, f64) {
+        let radius = data.accuracy.max(0.0) as f64;
+        let lat_delta = (radius / EARTH_RADIUS_M).to_degrees();
+        let min_lat = (data.latitude - lat_delta).max(-90.0);
+        let max_lat = (data.latitude + lat_delta).min(90.0);
+        let cos_lat = data.latitude.to_radians().cos();
+        let lon_delta = if cos_lat.abs() < 1e-9 {
+            f64::INFINITY
+        } else {
+            (radius / (EARTH_RADIUS_M * cos_lat)).to_degrees()
+        };
+        if lon_delta >= 180.0 || min_lat <= -90.0 || max_lat >= 90.0 {
+            return (min_lat, max_lat, -180.0, 180.0);
+        }
+
+        let wrap = |lon: f64| {
+            if (-180.0..=180.0).contains(&lon) {
+                lon
+            } else {
+                (lon + 180.0).rem_euclid(360.0) - 180.0
+            }
+        };
+        (
+            min_lat,
+            max_lat,
+            wrap(data.longitude - lon_delta),
+            wrap(data.longitude + lon_delta),
+        )
+    }
+
+    /// Estimates a position from three or more access points with known
+    /// locations and estimated distances in meters (e.g. from RSSI).
+    ///
+    /// Works on a local flat-earth projection around the APs and solves the
+    /// linearized circle equations by least squares. Returns `None` for fewer
+    /// than three APs or when the APs are (nearly) collinear. The returned
+    /// `accuracy` is the RMS range residual, floored at 1 m.
+    pub fn trilaterate(&self, aps: &[(GpsData, f32)]) -> Option<GpsData> {
+        if aps.len() < 3 {
+            return None;
+        }
+        let origin = &aps[0].0;
+        let points: Vec<(f64, f64, f64)> = aps
+            .iter()
+            .map(|(ap, distance)| {
+                let (x, y) = local_xy(origin, ap);
+                (x, y, *distance as f64)
+            })
+            .collect();
+
+        // Subtracting the first circle equation from the others leaves linear
+        // equations a*x + b*y = c; accumulate the 2x2 normal equations.
+        let (x0, y0, r0) = points[0];
+        let (mut aa, mut ab, mut bb, mut ac, mut bc) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for &(xi, yi, ri) in &points[1..] {
+            let a = 2.0 * (xi - x0);
+            let b = 2.0 * (yi - y0);
+            let c = r0 * r0 - ri * ri + xi * xi - x0 * x0 + yi * yi - y0 * y0;
+            aa += a * a;
+            ab += a * b;
+            bb += b * b;
+            ac += a * c;
+            bc += b * c;
+        }
+        let det = aa * bb - ab * ab;
+        if det.abs() < 1e-6 * (aa * bb).max(1.0) {
+            return None;
+        }
+        let x = (ac * bb - bc * ab) / det;
+        let y = (aa * bc - ab * ac) / det;
+
+        let residual = (points
+            .iter()
+            .map(|&(xi, yi, ri)| ((x - xi).hypot(y - yi) - ri).powi(2))
+            .sum::<f64>()
+            / points.len() as f64)
+            .sqrt();
+        let (latitude, longitude) = from_local_xy(origin, x, y);
+        Some(GpsData {
+            latitude,
+            longitude,
+            altitude: None,
+            accuracy: residual.max(1.0) as f32,
+            speed: None,
+            bearing: None,
+            timestamp: aps.iter().map(|(ap, _)| ap.timestamp).max().unwrap_or(0),
+        })
+    }
+
+    /// Scores how settled a sequence of WiFi scans is, from 0.0 to 1.0.
+    ///
+    /// The score is the mean Jaccard similarity of the BSSID sets of each pair
+    /// of consecutive scans, so it is 1.0 when every scan sees exactly the same
+    /// access points; scans that see nothing score 0.0. Fails with
+    /// `EmptyInput` for fewer than two scans.
+    pub fn scan_stability(&self, scans: &[Vec<WifiNetwork>]) -> Result<f32, SensorError> {
+        if scans.len() < 2 {
+            return Err(SensorError::EmptyInput(
+                "scan stability needs at least two scans".to_string(),
+            ));
+        }
+        let sets: Vec<HashSet<&str>> = scans
+            .iter()
+            .map(|scan| scan.iter().map(|n| n.bssid.as_str()).collect())
+            .collect();
+        let total: f32 = sets
+            .windows(2)
+            .map(|pair| {
+                let union = pair[0].union(&pair[1]).count();
+                if union == 0 {
+                    0.0
+                } else {
+                    pair[0].intersection(&pair[1]).count() as f32 / union as f32
+                }
+            })
+            .sum();
+        Ok(total / (sets.len() - 1) as f32)
+    }
+
+    /// Byte length of the compact JSON encoding of `data`, computed without
+    /// allocating the encoded string.
+    pub fn estimate_json_size<T: Serialize>(&self, data: &T) -> Result<usize, SensorError> {
+        let mut counter = ByteCounter(0);
+        serde_json::to_writer(&mut counter, data)
+            .map_err(|e| SensorError::DataError(e.to_string()))?;
+        Ok(counter.0)
+    }
+
+    /// Sum of [`estimate_json_size`](Self::estimate_json_size) over each item,
+    /// i.e. the payload size when items are sent individually.
+    pub fn estimate_json_size_batch<T: Serialize>(
+        &self,
+        items: &[T],
+    ) -> Result<usize, SensorError> {
+        items.iter().map(|item| self.estimate_json_size(item)).sum()
+    }
+
+    /// Altitude above mean sea level in meters, given the local QNH
+    /// (sea-level pressure) in hPa, using the ISA barometric formula.
+    pub fn altitude_qnh(&self, data: &PressureData, qnh_hpa: f32) -> f32 {
+        math::barometric_altitude(data.pressure, qnh_hpa)
+    }
+
+    /// Height above the reference field in meters, given the QFE (pressure at
+    /// field elevation) in hPa. Reads 0 on the field itself.
+    pub fn height_qfe(&self, data: &PressureData, qfe_hpa: f32) -> f32 {
+        math::barometric_altitude(data.pressure, qfe_hpa)
+    }
+
+    /// Simplifies a track with the Ramer-Douglas-Peucker algorithm, dropping
+    /// fixes that lie within `epsilon_m` meters of the simplified line.
+    ///
+    /// The first and last fixes are always kept.
+    pub fn simplify_track(&self, samples: &[GpsData], epsilon_m: f64) -> Vec<GpsData> {
+        if samples.len() < 3 {
+            return samples.to_vec();
+        }
+        let origin = &samples[0];
+        let points: Vec<(f64, f64)> = samples.iter().map(|s| local_xy(origin, s)).collect();
+
+        let mut keep = vec![false; samples.len()];
+        keep[0] = true;
+        keep[samples.len() - 1] = true;
+        let mut stack = vec![(0, samples.len() - 1)];
+        while let Some((start, end)) = stack.pop() {
+            let farthest = (start + 1..end)
+                .map(|i| (i, segment_distance(points[i], points[start], points[end])))
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((index, distance)) = farthest {
+                if distance > epsilon_m {
+                    keep[index] = true;
+                    stack.push((start, index));
+                    stack.push((index, end));
+                }
+            }
+        }
+
+        samples
+            .iter()
+            .zip(keep)
+            .filter(|(_, kept)| *kept)
+            .map(|(sample, _)| sample.clone())
+            .collect()
+    }
+
+    /// Like [`calculate_acceleration_magnitude`](Self::calculate_acceleration_magnitude),
+    /// but `None` when any axis or the result is not finite.
+    pub fn try_magnitude(&self, data: &AccelerometerData) -> Option<f32> {
+        if !(data.x.is_finite() && data.y.is_finite() && data.z.is_finite()) {
+            return None;
+        }
+        finite(self.calculate_acceleration_magnitude(data))
+    }
+
+    /// Like [`altitude_qnh`](Self::altitude_qnh), but `None` instead of NaN or
+    /// infinity for non-positive or non-finite pressures.
+    pub fn try_altitude_qnh(&self, data: &PressureData, qnh_hpa: f32) -> Option<f32> {
+        if data.pressure <= 0.0 || qnh_hpa <= 0.0 {
+            return None;
+        }
+        finite(self.altitude_qnh(data, qnh_hpa))
+    }
+
+    /// Like [`height_qfe`](Self::height_qfe), but `None` instead of NaN or
+    /// infinity for non-positive or non-finite pressures.
+    pub fn try_height_qfe(&self, data: &PressureData, qfe_hpa: f32) -> Option<f32> {
+        if data.pressure <= 0.0 || qfe_hpa <= 0.0 {
+            return None;
+        }
+        finite(self.height_qfe(data, qfe_hpa))
+    }
+
+    /// Splits the GPS ground speed into `(forward, lateral)` components in m/s
+    /// relative to the direction the device faces.
+    ///
+    /// Lateral is positive when drifting to the right of `heading_deg`. When the
+    /// fix has no `bearing`, travel is assumed to be along the heading. Returns
+    /// `(0.0, 0.0)` when speed is absent.
+    pub fn relative_velocity(&self, gps: &GpsData, heading_deg: f32) -> (f32, f32) {
+        let Some(speed) = gps.speed else {
+            return (0.0, 0.0);
+        };
+        let course = gps.bearing.unwrap_or(heading_deg);
+        let offset = (course - heading_deg).to_radians();
+        (speed * offset.cos(), speed * offset.sin())
+    }
+
+    /// Rate of heading change in degrees per second for a device that is
+    /// known to be stationary, so any change is sensor drift.
+    ///
+    /// Headings are unwrapped across north and fitted with a least-squares line
+    /// against time; the absolute slope is returned. Random jitter averages out,
+    /// so a healthy sensor reports close to 0. Fails with `EmptyInput` for
+    /// fewer than two samples.
+    pub fn heading_drift_rate(&self, samples: &[MagnetometerData]) -> Result<f32, SensorError> {
+        if samples.len() < 2 {
+            return Err(SensorError::EmptyInput(
+                "heading drift needs at least two samples".to_string(),
+            ));
+        }
+        let t0 = samples[0].timestamp;
+        let mut unwrapped = samples[0].heading;
+        let mut points = vec![(0.0_f64, unwrapped as f64)];
+        for pair in samples.windows(2) {
+            unwrapped += heading_difference(pair[0].heading, pair[1].heading);
+            let seconds = (pair[1].timestamp - t0) as f64 / 1000.0;
+            points.push((seconds, unwrapped as f64));
+        }
+
+        let n = points.len() as f64;
+        let mean_t = points.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_h = points.iter().map(|p| p.1).sum::<f64>() / n;
+        let covariance: f64 = points.iter().map(|p| (p.0 - mean_t) * (p.1 - mean_h)).sum();
+        let variance: f64 = points.iter().map(|p| (p.0 - mean_t).powi(2)).sum();
+        if variance == 0.0 {
+            return Ok(0.0);
+        }
+        Ok((covariance / variance).abs() as f32)
+    }
+
+    /// Encodes each axis as `round(value * scale)`, e.g. 9.81 becomes 9810 at
+    /// scale 1000. Values outside the `i32` range saturate.
+    pub fn to_fixed_point(&self, data: &AccelerometerData, scale: i32) -> (i32, i32, i32) {
+        let scale = scale as f32;
+        (
+            (data.x * scale).round() as i32,
+            (data.y * scale).round() as i32,
+            (data.z * scale).round() as i32,
+        )
+    }
+
+    /// Inverse of [`to_fixed_point`](Self::to_fixed_point). A `scale` of 0 is
+    /// treated as 1.
+    pub fn from_fixed_point(
+        &self,
+        values: (i32, i32, i32),
+        scale: i32,
+        timestamp: i64,
+        accuracy: i32,
+    ) -> AccelerometerData {
+        let scale = if scale == 0 { 1.0 } else { scale as f32 };
+        AccelerometerData {
+            x: values.0 as f32 / scale,
+            y: values.1 as f32 / scale,
+            z: values.2 as f32 / scale,
+            timestamp,
+            accuracy,
+        }
+    }
+
+    /// Great-circle distance in meters between two fixes using the Haversine
+    /// formula. Altitude is ignored.
+    pub fn distance_between(&self, a: &GpsData, b: &GpsData) -> f64 {
+        math::haversine(a.latitude, a.longitude, b.latitude, b.longitude)
+    }
+
+    /// Seconds to reach `target` at the current ground speed, or `None` when
+    /// the speed is absent or not positive.
+    pub fn eta_seconds(&self, current: &GpsData, target: &GpsData) -> Option<f32> {
+        let speed = current.speed.filter(|s| *s > 0.0)?;
+        Some((self.distance_between(current, target) / speed as f64) as f32)
+    }
+
+    /// Names the compass direction of `heading` in the requested style.
+    pub fn direction_name(&self, heading: f32, style: DirectionStyle) -> String {
+        let abbreviation = get_cardinal_direction(heading);
+        let name = match style {
+            DirectionStyle::Abbreviated => abbreviation,
+            DirectionStyle::Full => match abbreviation {
+                "N" => "North",
+                "NE" => "Northeast",
+                "E" => "East",
+                "SE" => "Southeast",
+                "S" => "South",
+                "SW" => "Southwest",
+                "W" => "West",
+                _ => "Northwest",
+            },
+        };
+        name.to_string()
+    }
+
+    /// ActiGraph-style activity counts: the absolute dynamic acceleration
+    /// integrated over consecutive epochs of `epoch_ms`.
+    ///
+    /// Dynamic acceleration is the magnitude minus the epoch's mean magnitude,
+    /// which removes gravity regardless of device orientation or calibration
+    /// offset. Each sample is weighted by the time since the previous sample, so
+    /// counts are in m/s and comparable across sample rates. Returns
+    /// `(epoch_start, count)` pairs for each epoch that contains samples, so no
+    /// samples means no epochs.
+    pub fn activity_counts(&self, samples: &[AccelerometerData], epoch_ms: i64) -> Vec<(i64, f32)> {
+        let Some(first) = samples.first() else {
+            return Vec::new();
+        };
+        let epoch_ms = epoch_ms.max(1);
+        let start = first.timestamp;
+
+        let mut counts = Vec::new();
+        let mut index = 0;
+        while index < samples.len() {
+            let epoch = (samples[index].timestamp - start).div_euclid(epoch_ms);
+            let end = samples[index..]
+                .iter()
+                .position(|s| (s.timestamp - start).div_euclid(epoch_ms) != epoch)
+                .map_or(samples.len(), |offset| index + offset);
+
+            let epoch_samples = &samples[index..end];
+            let magnitudes: Vec<f32> = epoch_samples
+                .iter()
+                .map(|s| self.calculate_acceleration_magnitude(s))
+                .collect();
+            let (mean, _) = mean_std(&magnitudes);
+            let count: f32 = (index..end)
+                .zip(&magnitudes)
+                .map(|(i, magnitude)| {
+                    let dt_ms = if i == 0 {
+                        0
+                    } else {
+                        samples[i].timestamp - samples[i - 1].timestamp
+                    };
+                    (magnitude - mean).abs() * dt_ms.max(0) as f32 / 1000.0
+                })
+                .sum();
+
+            counts.push((start + epoch * epoch_ms, count));
+            index = end;
+        }
+        counts
+    }
+
+    /// Converts a 0-360 heading to quadrant bearing notation, e.g. `"N45°E"`
+    /// or `"S30°W"`, rounded to whole degrees. Exact cardinal headings are
+    /// returned as a single letter (`"N"`, `"E"`, `"S"`, `"W"`).
+    pub fn to_quadrant_bearing(&self, heading: f32) -> String {
+        let degrees = normalize_heading(heading).round() as i32 % 360;
+        match degrees {
+            0 => "N".to_string(),
+            90 => "E".to_string(),
+            180 => "S".to_string(),
+            270 => "W".to_string(),
+            d if d < 90 => format!("N{}°E", d),
+            d if d < 180 => format!("S{}°E", 180 - d),
+            d if d < 270 => format!("S{}°W", d - 180),
+            d => format!("N{}°W", 360 - d),
+        }
+    }
+
+    /// Resamples two accelerometer streams onto a shared uniform grid at
+    /// `target_hz` over their overlapping time range, returning aligned pairs.
+    ///
+    /// Both streams must be sorted by timestamp. Values are linearly
+    /// interpolated; each output sample carries the grid timestamp and the lower
+    /// accuracy of its two neighbours. Returns an empty vector when the streams
+    /// do not overlap.
+    ///
+    /// Fails with `DataError` when `target_hz` is not a positive rate or the
+    /// grid would exceed one million samples (about 3 hours at 100 Hz).
+    pub fn align_streams(
+        &self,
+        a: &[AccelerometerData],
+        b: &[AccelerometerData],
+        target_hz: f32,
+    ) -> Result<Vec<(AccelerometerData, AccelerometerData)>, SensorError> {
+        const MAX_GRID_SAMPLES: f64 = 1_000_000.0;
+        if !target_hz.is_finite() || target_hz <= 0.0 {
+            return Err(SensorError::DataError(format!(
+                "target rate must be positive, got {target_hz} Hz"
+            )));
+        }
+        let (Some(a_first), Some(a_last), Some(b_first), Some(b_last)) =
+            (a.first(), a.last(), b.first(), b.last())
+        else {
+            return Ok(Vec::new());
+        };
+        let start = a_first.timestamp.max(b_first.timestamp);
+        let end = a_last.timestamp.min(b_last.timestamp);
+        if start > end {
+            return Ok(Vec::new());
+        }
+
+        let step_ms = 1000.0 / target_hz as f64;
+        let count = ((end - start) as f64 / step_ms).floor() + 1.0;
+        if count > MAX_GRID_SAMPLES {
+            return Err(SensorError::DataError(format!(
+                "{target_hz} Hz grid needs {count} samples, more than {MAX_GRID_SAMPLES}"
+            )));
+        }
+        Ok((0..count as usize)
+            .filter_map(|k| {
+                let t = start + (k as f64 * step_ms).round() as i64;
+                Some((
+                    interpolate_accelerometer(a, t)?,
+                    interpolate_accelerometer(b, t)?,
+                ))
+            })
+            .collect())
+    }
+
+    /// Blends a magnetometer heading with the GPS course over ground.
+    ///
+    /// GPS course is meaningless when standing still and accurate when moving,
+    /// so its weight ramps linearly from 0 at 1 m/s to 0.9 at 5 m/s and above.
+    /// Headings are averaged on the circle so 350° and 10° blend to 0°, not
+    /// 180°. Without a GPS course the magnetometer heading is returned as-is.
+    pub fn fuse_heading(&self, mag_heading: f32, gps_course: Option<f32>, speed_mps: f32) -> f32 {
+        const MIN_SPEED: f32 = 1.0;
+        const FULL_SPEED: f32 = 5.0;
+        const MAX_GPS_WEIGHT: f32 = 0.9;
+        let Some(course) = gps_course else {
+            return normalize_heading(mag_heading);
+        };
+
+        let gps_weight =
+            MAX_GPS_WEIGHT * ((speed_mps - MIN_SPEED) / (FULL_SPEED - MIN_SPEED)).clamp(0.0, 1.0);
+        let mag_weight = 1.0 - gps_weight;
+        let (mag, gps) = (mag_heading.to_radians(), course.to_radians());
+        let sin = mag_weight * mag.sin() + gps_weight * gps.sin();
+        let cos = mag_weight * mag.cos() + gps_weight * gps.cos();
+        normalize_heading(sin.atan2(cos).to_degrees())
+    }
+
+    /// One-line description of a recorded session: device, per-sensor sample
+    /// counts and duration, e.g.
+    /// `"Session pixel-7: 2 samples (accelerometer 1, gps 1) over 1.5 s"`.
+    /// Sensors without samples are omitted, and so are the parentheses when
+    /// no sensor has any: `"Session pixel-7: 0 samples over 0.0 s"`.
+    ///
+    /// Counts and duration come from the samples themselves, so the summary
+    /// is right even if the header is stale.
+    pub fn session_summary(&self, session: &SensorSession) -> String {
+        let metadata = &session.computed_metadata();
+        let counts: Vec<String> = metadata
+            .sample_counts
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(name, count)| format!("{} {}", name, count))
+            .collect();
+        let total: usize = metadata.sample_counts.values().sum();
+        let duration_s = (metadata.end_timestamp - metadata.start_timestamp) as f64 / 1000.0;
+        let breakdown = if counts.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", counts.join(", "))
+        };
+        format!(
+            "Session {}: {} samples{} over {:.1} s",
+            metadata.device_id, total, breakdown, duration_s
+        )
+    }
+
+    /// Exponentially smoothed signal strength in dBm for one access point.
+    ///
+    /// `history` holds `(timestamp, network)` observations in time order; only
+    /// entries matching `bssid` are used. `alpha` (clamped to 0.0-1.0) is the
+    /// weight of each new observation. Returns `None` if the BSSID never appears.
+    pub fn smooth_signal(
+        &self,
+        history: &[(i64, WifiNetwork)],
+        bssid: &str,
+        alpha: f32,
+    ) -> Option<i32> {
+        let alpha = alpha.clamp(0.0, 1.0);
+        history
+            .iter()
+            .filter(|(_, network)| network.bssid == bssid)
+            .map(|(_, network)| network.signal_strength as f32)
+            .reduce(|smoothed, signal| alpha * signal + (1.0 - alpha) * smoothed)
+            .map(|smoothed| smoothed.round() as i32)
+    }
+
+    /// Removes samples with duplicate timestamps, keeping the last sample for
+    /// each timestamp. Kept samples stay in their original relative order.
+    pub fn dedup_by_timestamp<T: SensorReading + Clone>(&self, samples: &[T]) -> Vec<T> {
+        let mut seen = HashSet::new();
+        let mut kept: Vec<T> = samples
+            .iter()
+            .rev()
+            .filter(|sample| seen.insert(sample.timestamp()))
+            .cloned()
+            .collect();
+        kept.reverse();
+        kept
+    }
+
+    /// Magnitude of the acceleration in the plane of the two axes other than
+    /// `exclude`, e.g. excluding Z gives the horizontal magnitude of a flat device.
+    pub fn planar_magnitude(&self, data: &AccelerometerData, exclude: Axis) -> f32 {
+        let (a, b) = match exclude {
+            Axis::X => (data.y, data.z),
+            Axis::Y => (data.x, data.z),
+            Axis::Z => (data.x, data.y),
+        };
+        a.hypot(b)
+    }
+
+    /// Initial great-circle bearing in degrees (0-359) from `from` to `to`.
+    pub fn bearing_between(&self, from: &GpsData, to: &GpsData) -> f32 {
+        normalize_heading(
+            math::bearing(from.latitude, from.longitude, to.latitude, to.longitude) as f32,
+        )
+    }
+
+    /// Formats the bearing from `from` to `to` with its compass direction,
+    /// e.g. `"90° E"`.
+    pub fn format_bearing(&self, from: &GpsData, to: &GpsData) -> String {
+        let bearing = self.bearing_between(from, to);
+        format!("{:.0}° {}", bearing, get_cardinal_direction(bearing))
+    }
+
+    /// Slope from `a` to `b` as a percentage: altitude change over horizontal
+    /// distance × 100. Negative when descending.
+    ///
+    /// Returns `None` when either fix lacks altitude or the fixes share a
+    /// position.
+    pub fn grade_percent(&self, a: &GpsData, b: &GpsData) -> Option<f32> {
+        let rise = b.altitude? - a.altitude?;
+        let run = self.distance_between(a, b);
+        if run <= 0.0 {
+            return None;
+        }
+        Some((rise / run * 100.0) as f32)
+    }
+
+    /// Average speed in m/s between two fixes, from the Haversine distance and
+    /// the difference of their timestamps.
+    ///
+    /// Fails with `DataError` unless `b` is strictly later than `a`.
+    pub fn speed_from_fixes(&self, a: &GpsData, b: &GpsData) -> Result<f32, SensorError> {
+        let elapsed_ms = b.timestamp - a.timestamp;
+        if elapsed_ms <= 0 {
+            return Err(SensorError::DataError(format!(
+                "Second fix must be later than the first (elapsed {} ms)",
+                elapsed_ms
+            )));
+        }
+        Ok((self.distance_between(a, b) / (elapsed_ms as f64 / 1000.0)) as f32)
+    }
+
+    /// Removes a pressure sensor's temperature drift.
+    ///
+    /// `coeff_hpa_per_c` is how much the sensor over-reads per °C above
+    /// `reference_c`, so the corrected pressure is
+    /// `pressure - coeff * (temperature - reference)`. At the reference
+    /// temperature the reading is unchanged.
+    pub fn temperature_compensate_pressure(
+        &self,
+        pressure: &PressureData,
+        temp: &TemperatureData,
+        coeff_hpa_per_c: f32,
+        reference_c: f32,
+    ) -> PressureData {
+        PressureData {
+            pressure: pressure.pressure - coeff_hpa_per_c * (temp.temperature - reference_c),
+            timestamp: pressure.timestamp,
+        }
+    }
+
+    /// Dew point in °C from the Magnus-Tetens approximation
+    /// (a = 17.62, b = 243.12 °C).
+    ///
+    /// `relative_humidity` must be 1-100%; values outside (including 0, where
+    /// the logarithm diverges) fail with `DataError`.
+    pub fn dew_point(
+        &self,
+        temp: &TemperatureData,
+        relative_humidity: f32,
+    ) -> Result<f32, SensorError> {
+        const A: f32 = 17.62;
+        const B: f32 = 243.12;
+        if !(1.0..=100.0).contains(&relative_humidity) {
+            return Err(SensorError::DataError(format!(
+                "Relative humidity must be 1-100% for dew point: {}",
+                relative_humidity
+            )));
+        }
+        let t = temp.temperature;
+        let gamma = (relative_humidity / 100.0).ln() + A * t / (B + t);
+        Ok(B * gamma / (A - gamma))
+    }
+
+    /// Splits a time-ordered track into segments wherever consecutive fixes are
+    /// more than `max_gap_ms` apart. Empty input yields no segments.
+    pub fn segment_track(&self, samples: &[GpsData], max_gap_ms: i64) -> Vec<Vec<GpsData>> {
+        let mut segments: Vec<Vec<GpsData>> = Vec::new();
+        for (i, fix) in samples.iter().enumerate() {
+            let starts_segment = i == 0 || fix.timestamp - samples[i - 1].timestamp > max_gap_ms;
+            match segments.last_mut() {
+                Some(segment) if !starts_segment => segment.push(fix.clone()),
+                _ => segments.push(vec![fix.clone()]),
+            }
+        }
+        segments
+    }
+
+    /// Apparent temperature in °C from the NOAA heat-index (Rothfusz)
+    /// regression, including NOAA's low- and high-humidity adjustments.
+    ///
+    /// The regression is only meaningful in the heat, so below 27°C the raw
+    /// temperature is returned. `humidity` is clamped to 0-100%.
+    pub fn heat_index(&self, temp: &TemperatureData, humidity: f32) -> f32 {
+        let t_c = temp.temperature;
+        if t_c < 27.0 {
+            return t_c;
+        }
+        let rh = humidity.clamp(0.0, 100.0);
+        let t = t_c * 9.0 / 5.0 + 32.0;
+
+        let mut hi = -42.379 + 2.049_015_3 * t + 10.143_331 * rh
+            - 0.224_755_4 * t * rh
+            - 0.006_837_83 * t * t
+            - 0.054_817_17 * rh * rh
+            + 0.001_228_74 * t * t * rh
+            + 0.000_852_82 * t * rh * rh
+            - 0.000_001_99 * t * t * rh * rh;
+        if rh < 13.0 && (80.0..=112.0).contains(&t) {
+            hi -= (13.0 - rh) / 4.0 * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+        } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+            hi += (rh - 85.0) / 10.0 * (87.0 - t) / 5.0;
+        }
+        (hi - 32.0) * 5.0 / 9.0
+    }
+
+    /// Coarse position from a WiFi scan as the signal-weighted centroid of the
+    /// known locations of the observed access points.
+    ///
+    /// Each matched AP is weighted by `10^(rssi / 20)`, which under free-space
+    /// path loss is proportional to 1/distance, so a stronger AP pulls harder.
+    /// BSSIDs missing from `ap_locations` are ignored; `None` if none match.
+    /// The result's `accuracy` is the weighted mean of the APs' accuracies.
+    pub fn fingerprint_position(
+        &self,
+        observed: &[WifiNetwork],
+        ap_locations: &HashMap<String, GpsData>,
+    ) -> Option<GpsData> {
+        let matches: Vec<(&GpsData, f64)> = observed
+            .iter()
+            .filter_map(|network| {
+                let location = ap_locations.get(&network.bssid)?;
+                Some((location, 10f64.powf(network.signal_strength as f64 / 20.0)))
+            })
+            .collect();
+        let total: f64 = matches.iter().map(|(_, w)| w).sum();
+        if matches.is_empty() || total <= 0.0 {
+            return None;
+        }
+
+        let weighted = |value: fn(&GpsData) -> f64| -> f64 {
+            matches.iter().map(|(ap, w)| value(ap) * w).sum::<f64>() / total
+        };
+        Some(GpsData {
+            latitude: weighted(|ap| ap.latitude),
+            longitude: weighted(|ap| ap.longitude),
+            altitude: None,
+            accuracy: weighted(|ap| ap.accuracy as f64) as f32,
+            speed: None,
+            bearing: None,
+            timestamp: matches
+                .iter()
+                .map(|(ap, _)| ap.timestamp)
+                .max()
+                .unwrap_or(0),
+        })
+    }
+
+    /// Whether the device is steady enough to capture a sharp photo or scan.
+    ///
+    /// Requires both the RMS of the dynamic acceleration (magnitude minus its
+    /// mean, m/s²) to be below `accel_rms_limit` and the RMS angular rate
+    /// (rad/s) to be below `gyro_rms_limit`. Without samples from both sensors
+    /// steadiness cannot be confirmed, so the answer is `false`.
+    pub fn is_steady_for_capture(
+        &self,
+        accel: &[AccelerometerData],
+        gyro: &[GyroscopeData],
+        accel_rms_limit: f32,
+        gyro_rms_limit: f32,
+    ) -> bool {
+        if accel.is_empty() || gyro.is_empty() {
+            return false;
+        }
+        let magnitudes: Vec<f32> = accel
+            .iter()
+            .map(|s| self.calculate_acceleration_magnitude(s))
+            .collect();
+        let (_, accel_rms) = mean_std(&magnitudes);
+        let gyro_rms = (gyro
+            .iter()
+            .map(|g| g.x * g.x + g.y * g.y + g.z * g.z)
+            .sum::<f32>()
+            / gyro.len() as f32)
+            .sqrt();
+        accel_rms < accel_rms_limit && gyro_rms < gyro_rms_limit
+    }
+
+    /// Heading as `{"degrees": 225.0, "cardinal": "SW"}` for human-readable logs.
+    pub fn heading_to_json(&self, data: &MagnetometerData) -> Value {
+        json!({
+            "degrees": data.heading,
+            "cardinal": get_cardinal_direction(data.heading),
+        })
+    }
+
+    /// Smallest circle enclosing every fix, as (center, radius in meters).
+    ///
+    /// Runs Welzl's algorithm on a local east/north projection around the
+    /// first fix. The center carries the worst accuracy and latest timestamp
+    /// of the inputs. Returns `None` for an empty slice.
+    pub fn bounding_circle(&self, samples: &[GpsData]) -> Option<(GpsData, f64)> {
+        let origin = samples.first()?;
+        let points: Vec<(f64, f64)> = samples.iter().map(|s| local_xy(origin, s)).collect();
+        let ((east, north), radius) = enclosing_circle(&points);
+        let (latitude, longitude) = from_local_xy(origin, east, north);
+        let center = GpsData {
+            latitude,
+            longitude,
+            altitude: None,
+            accuracy: samples.iter().map(|s| s.accuracy).fold(0.0, f32::max),
+            speed: None,
+            bearing: None,
+            timestamp: samples.iter().map(|s| s.timestamp).max().unwrap_or(0),
+        };
+        Some((center, radius))
+    }
+
+    /// Device (pitch, roll) in degrees from the gravity vector.
+    ///
+    /// Pitch is `atan2(-x, sqrt(y² + z²))` and roll is `atan2(y, z)`, using
+    /// the Android device axes. A flat, screen-up device reads (0, 0), as does
+    /// a zero vector, which has no orientation.
+    pub fn orientation_angles(&self, data: &AccelerometerData) -> (f32, f32) {
+        if self.calculate_acceleration_magnitude(data) < f32::EPSILON {
+            return (0.0, 0.0);
+        }
+        let pitch = (-data.x).atan2(data.y.hypot(data.z)).to_degrees();
+        let roll = data.y.atan2(data.z).to_degrees();
+        (pitch, roll)
+    }
+
+    /// Whether the sample looks like free fall: the magnitude drops below
+    /// `threshold` (around 2.0 m/s² works well) because a falling device
+    /// measures close to zero g.
+    pub fn is_free_fall(&self, data: &AccelerometerData, threshold: f32) -> bool {
+        self.calculate_acceleration_magnitude(data) < threshold
+    }
+
+    /// Combines several scans into one entry per BSSID, in the order each
+    /// BSSID was first seen. Scans are expected oldest first.
+    pub fn merge_scans_with(
+        &self,
+        scans: &[Vec<WifiNetwork>],
+        strategy: MergeStrategy,
+    ) -> Vec<WifiNetwork> {
+        let mut merged: Vec<(WifiNetwork, i64, usize)> = Vec::new();
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        for network in scans.iter().flatten() {
+            let Some(&i) = index.get(network.bssid.as_str()) else {
+                index.insert(&network.bssid, merged.len());
+                merged.push((network.clone(), network.signal_strength as i64, 1));
+                continue;
+            };
+            let (kept, sum, count) = &mut merged[i];
+            *sum += network.signal_strength as i64;
+            *count += 1;
+            let replace = match strategy {
+                MergeStrategy::Strongest => network.signal_strength > kept.signal_strength,
+                MergeStrategy::Average | MergeStrategy::Latest => true,
+            };
+            if replace {
+                *kept = network.clone();
+            }
+        }
+        merged
+            .into_iter()
+            .map(|(mut network, sum, count)| {
+                if strategy == MergeStrategy::Average {
+                    network.signal_strength = (sum as f64 / count as f64).round() as i32;
+                }
+                network
+            })
+            .collect()
+    }
+
+    /// Channel number for the network's frequency.
+    ///
+    /// Covers 2.4 GHz channels 1-14 and the 20 MHz channel centers of the
+    /// 5 GHz band (`(MHz - 5000) / 5`: 32-144 every 20 MHz from 5160 MHz and
+    /// 149-177 from 5745 MHz) and the 6 GHz band (`(MHz - 5950) / 5`: 1-233
+    /// every 20 MHz from 5955 MHz, plus channel 2 at 5935 MHz). Other
+    /// frequencies, including 5 MHz steps between 20 MHz centers, return
+    /// `None`.
+    pub fn wifi_channel(&self, network: &WifiNetwork) -> Option<u16> {
+        match network.frequency {
+            2484 => Some(14),
+            f @ 2412..=2472 if (f - 2407) % 5 == 0 => Some(((f - 2407) / 5) as u16),
+            f @ 5160..=5720 if (f - 5160) % 20 == 0 => Some(((f - 5000) / 5) as u16),
+            f @ 5745..=5885 if (f - 5745) % 20 == 0 => Some(((f - 5000) / 5) as u16),
+            5935 => Some(2),
+            f @ 5955..=7115 if (f - 5955) % 20 == 0 => Some(((f - 5950) / 5) as u16),
+            _ => None,
+        }
+    }
+
+    /// Vibration dose value (m/s^1.75) of the window, after ISO 2631-1:
+    /// `(∫ a(t)⁴ dt)^(1/4)` over the dynamic acceleration (magnitude minus its
+    /// mean). The ISO frequency weighting filters are not applied.
+    ///
+    /// Fails with `EmptyInput` for an empty window and with `DataError` for a
+    /// non-positive sample rate.
+    pub fn vibration_dose_value(
+        &self,
+        samples: &[AccelerometerData],
+        sample_rate_hz: f32,
+    ) -> Result<f32, SensorError> {
+        if samples.is_empty() {
+            return Err(SensorError::EmptyInput(
+                "vibration dose value needs accelerometer samples".to_string(),
+            ));
+        }
+        if sample_rate_hz <= 0.0 {
+            return Err(SensorError::DataError(format!(
+                "sample rate must be positive, got {sample_rate_hz} Hz"
+            )));
+        }
+        let magnitudes: Vec<f32> = samples
+            .iter()
+            .map(|s| self.calculate_acceleration_magnitude(s))
+            .collect();
+        let (mean, _) = mean_std(&magnitudes);
+        let dt = 1.0 / sample_rate_hz;
+        let integral: f32 = magnitudes.iter().map(|m| (m - mean).powi(4) * dt).sum();
+        Ok(integral.powf(0.25))
+    }
+
+    /// Band of the network's frequency: "2.4 GHz", "5 GHz", "6 GHz" or
+    /// "Unknown".
+    pub fn wifi_band(&self, network: &WifiNetwork) -> &'static str {
+        self.classify_band(network)
+            .map_or("Unknown", WifiBand::as_str)
+    }
+
+    /// Typed band of the network's frequency, `None` outside the WiFi bands.
+    pub fn classify_band(&self, network: &WifiNetwork) -> Option<WifiBand> {
+        match network.frequency {
+            2400..=2500 => Some(WifiBand::Band2_4GHz),
+            5150..=5895 => Some(WifiBand::Band5GHz),
+            5925..=7125 => Some(WifiBand::Band6GHz),
+            _ => None,
+        }
+    }
+
+    /// Networks from `networks` on `band`, in their original order.
+    pub fn filter_by_band(&self, networks: &[WifiNetwork], band: WifiBand) -> Vec<WifiNetwork> {
+        networks
+            .iter()
+            .filter(|n| self.classify_band(n) == Some(band))
+            .cloned()
+            .collect()
+    }
+
+    /// Whether the network is on the 6 GHz band, which requires WiFi 6E.
+    pub fn is_wifi6e(&self, network: &WifiNetwork) -> bool {
+        self.classify_band(network) == Some(WifiBand::Band6GHz)
+    }
+
+    /// Position of `point` relative to `origin` in local East/North/Up meters.
+    ///
+    /// Uses a tangent-plane approximation, accurate over a few kilometers.
+    /// Up is the altitude difference, or 0 when either altitude is missing.
+    pub fn to_enu(&self, origin: &GpsData, point: &GpsData) -> (f64, f64, f64) {
+        let (east, north) = local_xy(origin, point);
+        let up = match (origin.altitude, point.altitude) {
+            (Some(from), Some(to)) => to - from,
+            _ => 0.0,
+        };
+        (east, north, up)
+    }
+
+    /// Rough distance in meters to the access point from the log-distance
+    /// path-loss model, `10^((tx_power - rssi) / (10 · n))`.
+    ///
+    /// `tx_power` is the RSSI measured 1 m from the access point. An RSSI of
+    /// 0 dBm or more is the platform's "no reading" value, so the distance is
+    /// reported as infinite and such networks sort last by proximity.
+    pub fn estimate_wifi_distance(&self, network: &WifiNetwork, tx_power: i32) -> f64 {
+        /// Path-loss exponent for free space; indoor values run 2.7-4.
+        const PATH_LOSS_EXPONENT: f64 = 2.0;
+        if network.signal_strength >= 0 {
+            return f64::INFINITY;
+        }
+        let loss = (tx_power - network.signal_strength) as f64;
+        10f64.powf(loss / (10.0 * PATH_LOSS_EXPONENT))
+    }
+
+    /// Zero crossings per second of `axis` around its mean over the window.
+    ///
+    /// Deviations within 1e-6 m/s² of the mean count as no crossing, so a
+    /// still axis reads 0. Fewer than two samples or a window without
+    /// elapsed time also read 0.
+    pub fn zero_crossing_rate(&self, samples: &[AccelerometerData], axis: Axis) -> f32 {
+        let values: Vec<f32> = samples
+            .iter()
+            .map(|s| match axis {
+                Axis::X => s.x,
+                Axis::Y => s.y,
+                Axis::Z => s.z,
+            })
+            .collect();
+        mean_crossing_rate(&values, window_duration_s(samples))
+    }
+
+    pub fn speed_kmh(&self, data: &GpsData) -> Option<f32> {
+        data.speed.map(|mps| mps * 3.6)
+    }
+
+    pub fn speed_mph(&self, data: &GpsData) -> Option<f32> {
+        data.speed.map(|mps| mps * 2.236_936)
+    }
+
+    /// Formats speed as `"48.2 km/h (30.0 mph)"`, or `"N/A"` without a speed.
+    pub fn format_speed(&self, data: &GpsData) -> String {
+        match (self.speed_kmh(data), self.speed_mph(data)) {
+            (Some(kmh), Some(mph)) => format!("{kmh:.1} km/h ({mph:.1} mph)"),
+            _ => "N/A".to_string(),
+        }
+    }
+
+    /// Names of the derived metrics this service computes; see [`METRICS`].
+    pub fn available_metrics(&self) -> Vec<&'static str> {
+        METRICS.to_vec()
+    }
+
+    /// Formats the fix in degrees/minutes/seconds, e.g.
+    /// `37°46'29.6" N, 122°25'9.8" W`.
+    pub fn format_gps_dms(&self, data: &GpsData) -> String {
+        let latitude = dms(data.latitude, if data.latitude < 0.0 { 'S' } else { 'N' });
+        let longitude = dms(data.longitude, if data.longitude < 0.0 { 'W' } else { 'E' });
+        format!("{latitude}, {longitude}")
+    }
+
+    /// Signed distance in meters from `point` to the great-circle route from
+    /// `path_start` through `path_end`: positive right of the direction of
+    /// travel, negative left.
+    pub fn cross_track_distance(
+        &self,
+        point: &GpsData,
+        path_start: &GpsData,
+        path_end: &GpsData,
+    ) -> f64 {
+        math::cross_track_distance(
+            (point.latitude, point.longitude),
+            (path_start.latitude, path_start.longitude),
+            (path_end.latitude, path_end.longitude),
+        )
+    }
+
+    /// Encodes the fix as a GeoJSON `Feature` with a `Point` geometry.
+    ///
+    /// Coordinates are `[longitude, latitude]` as GeoJSON requires. Altitude
+    /// and speed are left out of the properties when unknown.
+    pub fn gps_to_geojson(&self, data: &GpsData) -> String {
+        let mut properties = json!({
+            "accuracy": data.accuracy,
+            "timestamp": data.timestamp,
+        });
+        if let Some(altitude) = data.altitude {
+            properties["altitude"] = json!(altitude);
+        }
+        if let Some(speed) = data.speed {
+            properties["speed"] = json!(speed);
+        }
+        json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "Point",
+                "coordinates": [data.longitude, data.latitude],
+            },
+            "properties": properties,
+        })
+        .to_string()
+    }
+
+    /// (timestamp, altitude) pairs with altitude smoothed by a trailing
+    /// moving average of `smoothing_window` fixes, so noise does not inflate
+    /// elevation gain. Fixes without altitude are skipped.
+    pub fn elevation_profile(
+        &self,
+        samples: &[GpsData],
+        smoothing_window: usize,
+    ) -> Vec<(i64, f64)> {
+        let (timestamps, altitudes): (Vec<i64>, Vec<f32>) = samples
+            .iter()
+            .filter_map(|s| s.altitude.map(|altitude| (s.timestamp, altitude as f32)))
+            .unzip();
+        let smoothed = MovingAverageFilter::new(smoothing_window).filter(&altitudes);
+        timestamps
+            .into_iter()
+            .zip(smoothed.into_iter().map(f64::from))
+            .collect()
+    }
+
+    /// CRC32 of the compact JSON encoding of `data`, for detecting corrupted
+    /// samples in storage or transit.
+    #[cfg(feature = "checksum")]
+    pub fn checksum(&self, data: &AccelerometerData) -> u32 {
+        let mut writer = CrcWriter(crc32fast::Hasher::new());
+        // Writing into the hasher cannot fail and the struct always encodes.
+        let _ = serde_json::to_writer(&mut writer, data);
+        writer.0.finalize()
+    }
+
+    /// Whether `data` still matches a checksum taken by
+    /// [`checksum`](Self::checksum).
+    #[cfg(feature = "checksum")]
+    pub fn verify_checksum(&self, data: &AccelerometerData, expected: u32) -> bool {
+        self.checksum(data) == expected
+    }
+
+    /// CSV report of a scan: a header row, then one row per network with its
+    /// derived channel (empty when unknown) and band. Text fields are quoted
+    /// when they contain commas, quotes or line breaks.
+    pub fn wifi_to_csv(&self, networks: &[WifiNetwork]) -> String {
+        let mut csv = String::from("ssid,bssid,signal_strength,frequency,security,channel,band\n");
+        for network in networks {
+            let channel = self
+                .wifi_channel(network)
+                .map_or_else(String::new, |c| c.to_string());
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{}\n",
+                csv_field(&network.ssid),
+                csv_field(&network.bssid),
+                network.signal_strength,
+                network.frequency,
+                csv_field(&network.security),
+                channel,
+                self.wifi_band(network)
+            ));
+        }
+        csv
+    }
+
+    /// Angle in degrees (-180 to 180] to turn from `current_heading` to face
+    /// `target`: positive clockwise (right), negative left, ~0 dead ahead.
+    pub fn relative_bearing(
+        &self,
+        current: &GpsData,
+        target: &GpsData,
+        current_heading: f32,
+    ) -> f32 {
+        heading_difference(current_heading, self.bearing_between(current, target))
+    }
+
+    /// JSON Lines encoding: one compact JSON object per line, each line
+    /// ending in `\n`. An empty slice yields an empty string.
+    pub fn to_jsonl<T: Serialize>(&self, items: &[T]) -> Result<String, SensorError> {
+        let mut out = String::new();
+        for item in items {
+            out.push_str(&self.to_json(item)?);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// [`to_jsonl`](Self::to_jsonl) for accelerometer readings, which always
+    /// serialize.
+    pub fn accelerometer_to_jsonl(&self, readings: &[AccelerometerData]) -> String {
+        self.to_jsonl(readings).unwrap_or_default()
+    }
+
+    /// Indices of `values` whose z-score against the whole slice exceeds
+    /// `threshold` in magnitude. A flat series has no outliers, so it
+    /// returns an empty vector instead of dividing by zero.
+    pub fn detect_anomalies(&self, values: &[f32], threshold: f32) -> Vec<usize> {
+        if values.is_empty() {
+            return Vec::new();
+        }
+        let (mean, std_dev) = mean_std(values);
+        // Relative tolerance: summing large equal values leaves rounding noise.
+        if std_dev <= (mean.abs() + 1.0) * 1e-6 {
+            return Vec::new();
+        }
+        values
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| ((*v - mean) / std_dev).abs() > threshold)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Reduces a station pressure to mean sea level (QNH, hPa) given the
+    /// station elevation in meters, using the ISA barometric formula. This is
+    /// the inverse of [`altitude_qnh`](Self::altitude_qnh), so at 0 m it
+    /// returns the station pressure unchanged.
+    pub fn station_to_qnh(&self, station_pressure: &PressureData, station_elevation_m: f32) -> f32 {
+        math::reference_pressure(station_pressure.pressure, station_elevation_m)
+    }
+
+    /// Fixed-length feature vector of a window for activity recognition.
+    ///
+    /// Seven features for each of x, y, z and the magnitude, in that order,
+    /// giving 28 values: mean, standard deviation, min, max, RMS,
+    /// zero-crossing rate (crossings of the mean per second) and dominant
+    /// frequency (Hz). The sample rate is taken from the timestamps. An empty
+    /// window yields all zeros.
+    pub fn extract_features(&self, samples: &[AccelerometerData]) -> Vec<f32> {
+        const FEATURES_PER_CHANNEL: usize = 7;
+        if samples.is_empty() {
+            return vec![0.0; 4 * FEATURES_PER_CHANNEL];
+        }
+        let duration_s = window_duration_s(samples);
+        let sample_rate_hz = if duration_s > 0.0 {
+            (samples.len() - 1) as f32 / duration_s
+        } else {
+            0.0
+        };
+        let channels: [Vec<f32>; 4] = [
+            samples.iter().map(|s| s.x).collect(),
+            samples.iter().map(|s| s.y).collect(),
+            samples.iter().map(|s| s.z).collect(),
+            samples
+                .iter()
+                .map(|s| self.calculate_acceleration_magnitude(s))
+                .collect(),
+        ];
+
+        let mut features = Vec::with_capacity(4 * FEATURES_PER_CHANNEL);
+        for values in &channels {
+            let (mean, std_dev) = mean_std(values);
+            features.extend([
+                mean,
+                std_dev,
+                values.iter().copied().fold(f32::INFINITY, f32::min),
+                values.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+                (values.iter().map(|v| v * v).sum::<f32>() / values.len() as f32).sqrt(),
+                mean_crossing_rate(values, duration_s),
+                dominant_frequency(values, sample_rate_hz),
+            ]);
+        }
+        features
+    }
+
+    /// Grid convergence in degrees at the fix for a transverse Mercator grid
+    /// with the given central meridian: the angle from true north to grid
+    /// north, positive when grid north lies east of true north (east of the
+    /// central meridian in the northern hemisphere).
+    pub fn grid_convergence(&self, data: &GpsData, central_meridian_deg: f64) -> f64 {
+        let delta_lon = (data.longitude - central_meridian_deg).to_radians();
+        (delta_lon.tan() * data.latitude.to_radians().sin())
+            .atan()
+            .to_degrees()
+    }
+
+    /// Magnetic heading converted to a grid bearing (0-360): declination
+    /// takes it to true north (see [`apply_declination`]), then the grid
+    /// convergence at `position` takes it to grid north.
+    pub fn heading_to_grid(
+        &self,
+        data: &MagnetometerData,
+        declination: f32,
+        position: &GpsData,
+        central_meridian_deg: f64,
+    ) -> f32 {
+        let true_heading = apply_declination(data.heading, declination);
+        normalize_heading(
+            true_heading - self.grid_convergence(position, central_meridian_deg) as f32,
+        )
+    }
+
+    /// Batch [`RouteSummary`] of a track, with the same rules as
+    /// [`TrackRecorder`].
+    pub fn route_summary(&self, samples: &[GpsData]) -> RouteSummary {
+        let mut recorder = TrackRecorder::new();
+        samples.iter().for_each(|fix| recorder.push(fix.clone()));
+        recorder.stats()
+    }
+
+    /// Coarse device orientation from the axis that dominates gravity.
+    ///
+    /// Uses the Android device axes: +x toward the right edge, +y toward the
+    /// top edge, +z out of the screen. At rest the accelerometer reads +g on
+    /// the axis pointing up, so:
+    ///
+    /// | Dominant | Orientation      |
+    /// |----------|------------------|
+    /// | +y       | "PortraitUp"     |
+    /// | -y       | "PortraitDown"   |
+    /// | +x       | "LandscapeLeft"  |
+    /// | -x       | "LandscapeRight" |
+    /// | +z       | "FaceUp"         |
+    /// | -z       | "FaceDown"       |
+    ///
+    /// "LandscapeLeft" is turned counter-clockwise (right edge up) and
+    /// "LandscapeRight" clockwise (left edge up). Ties are resolved in z, y, x
+    /// order, so a zero vector reads "FaceUp".
+    pub fn detect_orientation(&self, data: &AccelerometerData) -> &'static str {
+        let (ax, ay, az) = (data.x.abs(), data.y.abs(), data.z.abs());
+        if az >= ay && az >= ax {
+            if data.z >= 0.0 {
+                "FaceUp"
+            } else {
+                "FaceDown"
+            }
+        } else if ay >= ax {
+            if data.y > 0.0 {
+                "PortraitUp"
+            } else {
+                "PortraitDown"
+            }
+        } else if data.x > 0.0 {
+            "LandscapeLeft"
+        } else {
+            "LandscapeRight"
+        }
+    }
+
+    /// Timestamped alerts for each time temperature or humidity leaves its
+    /// `(min, max)` range.
+    ///
+    /// Both streams are walked on their combined timeline; at each timestamp
+    /// the other metric is taken from its nearest sample. An alert fires on
+    /// the transition out of range, not again while the excursion lasts.
+    pub fn comfort_alerts(
+        &self,
+        temps: &[TemperatureData],
+        humidities: &[HumidityData],
+        temp_range: (f32, f32),
+        humidity_range: (f32, f32),
+    ) -> Vec<(i64, String)> {
+        let mut timeline: Vec<i64> = temps
+            .iter()
+            .map(|t| t.timestamp)
+            .chain(humidities.iter().map(|h| h.timestamp))
+            .collect();
+        timeline.sort_unstable();
+        timeline.dedup();
+
+        let describe = |name: &str, value: f32, unit: &str, (min, max): (f32, f32)| {
+            let side = if value < min { "below" } else { "above" };
+            format!("{name} {value:.1}{unit} {side} {min:.1}-{max:.1}{unit}")
+        };
+        let mut alerts = Vec::new();
+        let (mut temp_out, mut humidity_out) = (false, false);
+        for t in timeline {
+            if let Some(temp) = nearest_by_timestamp(temps, t) {
+                let out = !(temp_range.0..=temp_range.1).contains(&temp.temperature);
+                if out && !temp_out {
+                    alerts.push((
+                        t,
+                        describe("Temperature", temp.temperature, "°C", temp_range),
+                    ));
+                }
+                temp_out = out;
+            }
+            if let Some(humidity) = nearest_by_timestamp(humidities, t) {
+                let out = !(humidity_range.0..=humidity_range.1).contains(&humidity.humidity);
+                if out && !humidity_out {
+                    alerts.push((
+                        t,
+                        describe("Humidity", humidity.humidity, "%", humidity_range),
+                    ));
+                }
+                humidity_out = out;
+            }
+        }
+        alerts
+    }
+
+    /// Track length in meters that ignores GPS glitches.
+    ///
+    /// A fix whose implied speed from the last accepted fix exceeds
+    /// `max_speed_mps` is treated as a glitch and dropped, so the segment is
+    /// measured from the last good fix to the next plausible one instead.
+    /// Fixes that move without any time passing count as glitches too.
+    pub fn track_distance_filtered(&self, samples: &[GpsData], max_speed_mps: f32) -> f64 {
+        let Some(mut last) = samples.first() else {
+            return 0.0;
+        };
+        let mut total = 0.0;
+        for fix in &samples[1..] {
+            let distance = self.distance_between(last, fix);
+            let seconds = (fix.timestamp - last.timestamp) as f64 / 1000.0;
+            let plausible = if seconds > 0.0 {
+                distance / seconds <= max_speed_mps as f64
+            } else {
+                distance == 0.0
+            };
+            if plausible {
+                total += distance;
+                last = fix;
+            }
+        }
+        total
+    }
+
+    /// UTM coordinates of the fix as (zone, latitude band, easting, northing)
+    /// in meters on the WGS84 ellipsoid.
+    ///
+    /// Northings in the southern hemisphere carry the usual 10,000 km false
+    /// northing. The Norway and Svalbard zone exceptions are applied. UTM is
+    /// only defined from 80°S to 84°N (the poles use UPS); other latitudes
+    /// fail with `DataError`.
+    pub fn gps_to_utm(&self, data: &GpsData) -> Result<(u8, char, f64, f64), SensorError> {
+        const SCALE: f64 = 0.9996;
+        const FALSE_EASTING: f64 = 500_000.0;
+        const FALSE_NORTHING_SOUTH: f64 = 10_000_000.0;
+        const BANDS: &[u8] = b"CDEFGHJKLMNPQRSTUVWX";
+
+        let (lat, lon) = (data.latitude, data.longitude);
+        if !(-80.0..=84.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+            return Err(SensorError::DataError(format!(
+                "({lat}, {lon}) is outside the UTM grid"
+            )));
+        }
+        let band = BANDS[(((lat + 80.0) / 8.0) as usize).min(BANDS.len() - 1)] as char;
+        let zone = utm_zone(lat, lon);
+
+        let central_meridian = zone as f64 * 6.0 - 183.0;
+        let (x, y) = math::transverse_mercator(lat, lon, central_meridian);
+        let easting = FALSE_EASTING + SCALE * x;
+        let northing = SCALE * y + if lat < 0.0 { FALSE_NORTHING_SOUTH } else { 0.0 };
+        Ok((zone, band, easting, northing))
+    }
+
+    /// Average step length in meters by the Weinberg model,
+    /// `K · (a_max - a_min)^¼`, with `K` scaled by the walker's height.
+    ///
+    /// The acceleration magnitude is split into steps at its upward mean
+    /// crossings and the model is applied to each step's peak-to-valley
+    /// amplitude (m/s²). Without a complete step the whole window is used.
+    /// A flat signal gives 0.0. Fails with `EmptyInput` when there are no
+    /// samples and with `DataError` for a non-positive height.
+    pub fn estimate_step_length(
+        &self,
+        samples: &[AccelerometerData],
+        height_m: f32,
+    ) -> Result<f32, SensorError> {
+        /// Weinberg constant per meter of height; about 0.44 for a 1.75 m adult.
+        const K_PER_METER: f32 = 0.25;
+        if samples.is_empty() {
+            return Err(SensorError::EmptyInput(
+                "step length needs accelerometer samples".to_string(),
+            ));
+        }
+        if height_m <= 0.0 {
+            return Err(SensorError::DataError(format!(
+                "height must be positive, got {height_m} m"
+            )));
+        }
+        let magnitudes: Vec<f32> = samples
+            .iter()
+            .map(|s| self.calculate_acceleration_magnitude(s))
+            .collect();
+        let (mean, _) = mean_std(&magnitudes);
+        let crossings: Vec<usize> = (1..magnitudes.len())
+            .filter(|&i| magnitudes[i - 1] < mean && magnitudes[i] >= mean)
+            .collect();
+        let steps: Vec<&[f32]> = if crossings.len() < 2 {
+            vec![&magnitudes]
+        } else {
+            crossings
+                .windows(2)
+                .map(|pair| &magnitudes[pair[0]..pair[1]])
+                .collect()
+        };
+
+        let k = K_PER_METER * height_m;
+        let total: f32 = steps
+            .iter()
+            .map(|step| {
+                let max = step.iter().copied().fold(f32::MIN, f32::max);
+                let min = step.iter().copied().fold(f32::MAX, f32::min);
+                k * (max - min).powf(0.25)
+            })
+            .sum();
+        Ok(total / steps.len() as f32)
+    }
+
+    /// Standard base-32 geohash of the fix with `precision` characters,
+    /// clamped to 1-12 (12 characters is already finer than a centimeter).
+    pub fn gps_to_geohash(&self, data: &GpsData, precision: usize) -> String {
+        const BASE32: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+        let precision = precision.clamp(1, 12);
+        let mut latitude = (-90.0, 90.0);
+        let mut longitude = (-180.0, 180.0);
+        let mut hash = String::with_capacity(precision);
+        let mut even_bit = true;
+        while hash.len() < precision {
+            let mut index = 0;
+            for _ in 0..5 {
+                // Bits alternate, starting with longitude.
+                let (range, value) = if even_bit {
+                    (&mut longitude, data.longitude)
+                } else {
+                    (&mut latitude, data.latitude)
+                };
+                let mid = (range.0 + range.1) / 2.0;
+                index <<= 1;
+                if value >= mid {
+                    index |= 1;
+                    range.0 = mid;
+                } else {
+                    range.1 = mid;
+                }
+                even_bit = !even_bit;
+            }
+            hash.push(BASE32[index] as char);
+        }
+        hash
+    }
+}
+
+/// Summary statistics over a sliding window of the most recent values.
+///
+/// Once `capacity` values are held, each push evicts the oldest. Aggregates
+/// return `None` while the window is empty.
+#[derive(Debug, Clone)]
+pub struct RollingStats {
+    capacity: usize,
+    values: VecDeque<f32>,
+}
+
+impl RollingStats {
+    /// A capacity of 0 is treated as 1.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            values: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, value: f32) {
+        if self.values.len() == self.capacity {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn mean(&self) -> Option<f32> {
+        if self.values.is_empty() {
+            return None;
+        }
+        Some(self.values.iter().sum::<f32>() / self.values.len() as f32)
+    }
+
+    pub fn min(&self) -> Option<f32> {
+        self.values.iter().copied().reduce(f32::min)
+    }
+
+    pub fn max(&self) -> Option<f32> {
+        self.values.iter().copied().reduce(f32::max)
+    }
+
+    /// Population standard deviation of the window.
+    pub fn std_dev(&self) -> Option<f32> {
+        let mean = self.mean()?;
+        let variance =
+            self.values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / self.values.len() as f32;
+        Some(variance.sqrt())
+    }
+}
+
+/// Pedometer step detection from accelerometer samples.
+///
+/// A step is a local peak in the acceleration magnitude that exceeds
+/// `threshold` (m/s², so a little above gravity) and comes at least
+/// `refractory_ms` after the previous step. Samples older than the last one
+/// seen are ignored.
+#[derive(Debug, Clone)]
+pub struct StepDetector {
+    threshold: f32,
+    refractory_ms: i64,
+    /// (timestamp, magnitude) of the sample before `candidate`.
+    previous: Option<(i64, f32)>,
+    /// Most recent sample; a peak if the next one is lower.
+    candidate: Option<(i64, f32)>,
+    last_step: Option<i64>,
+    steps: usize,
+}
+
+impl StepDetector {
+    pub fn new(threshold: f32, refractory_ms: i64) -> Self {
+        Self {
+            threshold,
+            refractory_ms: refractory_ms.max(0),
+            previous: None,
+            candidate: None,
+            last_step: None,
+            steps: 0,
+        }
+    }
+
+    /// Feeds a sample and returns true when it confirms a new step.
+    ///
+    /// A peak is only known once the following sample is lower, so the step
+    /// is reported one sample late and attributed to the peak's timestamp.
+    pub fn process(&mut self, data: &AccelerometerData) -> bool {
+        if let Some((last, _)) = self.candidate {
+            if data.timestamp < last {
+                return false;
+            }
+        }
+        let current = (data.timestamp, math::magnitude(data.x, data.y, data.z));
+
+        let mut detected = false;
+        if let (Some(previous), Some(candidate)) = (self.previous, self.candidate) {
+            let is_peak = candidate.1 > self.threshold
+                && candidate.1 >= previous.1
+                && candidate.1 > current.1;
+            let rested = self
+                .last_step
+                .is_none_or(|last| candidate.0 - last >= self.refractory_ms);
+            if is_peak && rested {
+                self.last_step = Some(candidate.0);
+                self.steps += 1;
+                detected = true;
+            }
+        }
+        self.previous = self.candidate;
+        self.candidate = Some(current);
+        detected
+    }
+
+    pub fn step_count(&self) -> usize {
+        self.steps
+    }
+}
+
+/// How [`SensorService::merge_scans_with`] combines repeated sightings of
+/// the same BSSID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Keep the sighting with the strongest signal.
+    Strongest,
+    /// Keep the latest sighting with the signal averaged over all sightings.
+    Average,
+    /// Keep the sighting from the most recent scan.
+    Latest,
+}
+
+/// Direction of a turn reported by [`TurnDetector`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurnDirection {
+    Left,
+    Right,
+}
+
+/// Debounced turn detection for turn-by-turn navigation.
+///
+/// A turn is a heading change of at least `turn_threshold_deg` within
+/// `min_interval_ms`, so slow drift never adds up to a turn. After a turn is
+/// reported, no other is reported for `min_interval_ms`. Updates older than
+/// the previous one are ignored.
+#[derive(Debug, Clone)]
+pub struct TurnDetector {
+    turn_threshold_deg: f32,
+    min_interval_ms: i64,
+    /// (timestamp, heading) pairs inside the detection window, oldest first.
+    window: VecDeque<(i64, f32)>,
+    last_turn: Option<i64>,
+}
+
+impl TurnDetector {
+    pub fn new(turn_threshold_deg: f32, min_interval_ms: i64) -> Self {
+        Self {
+            turn_threshold_deg: turn_threshold_deg.abs(),
+            min_interval_ms: min_interval_ms.max(0),
+            window: VecDeque::new(),
+            last_turn: None,
+        }
+    }
+
+    pub fn update(&mut self, heading: f32, timestamp: i64) -> Option<TurnDirection> {
+        if self
+            .window
+            .back()
+            .is_some_and(|&(last, _)| timestamp < last)
+        {
+            return None;
+        }
+        while self
+            .window
+            .front()
+            .is_some_and(|&(t, _)| timestamp - t > self.min_interval_ms)
+        {
+            self.window.pop_front();
+        }
+
+        let change = self
+            .window
+            .front()
+            .map_or(0.0, |&(_, start)| heading_difference(start, heading));
+        let rested = self
+            .last_turn
+            .is_none_or(|last| timestamp - last >= self.min_interval_ms);
+        if change.abs() >= self.turn_threshold_deg && rested {
+            self.last_turn = Some(timestamp);
+            self.window.clear();
+            self.window.push_back((timestamp, heading));
+            return Some(if change > 0.0 {
+                TurnDirection::Right
+            } else {
+                TurnDirection::Left
+            });
+        }
+        self.window.push_back((timestamp, heading));
+        None
+    }
+}
+
+/// A WiFi frequency band, as classified by [`SensorService::classify_band`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WifiBand {
+    /// 2400-2500 MHz.
+    Band2_4GHz,
+    /// 5150-5895 MHz.
+    Band5GHz,
+    /// 5925-7125 MHz (WiFi 6E).
+    Band6GHz,
+}
+
+impl WifiBand {
+    /// Display label, e.g. "2.4 GHz".
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WifiBand::Band2_4GHz => "2.4 GHz",
+            WifiBand::Band5GHz => "5 GHz",
+            WifiBand::Band6GHz => "6 GHz",
+        }
+    }
+}
+
+/// Barometric trend over the most recent pressure readings.
+///
+/// Keeps the last `window` readings and compares the mean of the newer half
+/// with the mean of the older half: a rise or fall larger than
+/// `threshold_hpa` is "Rising" or "Falling", anything smaller "Steady".
+#[derive(Debug, Clone)]
+pub struct PressureTrend {
+    window: usize,
+    threshold_hpa: f32,
+    readings: VecDeque<f32>,
+}
+
+impl PressureTrend {
+    /// A window below 2 is treated as 2.
+    pub fn new(window: usize, threshold_hpa: f32) -> Self {
+        let window = window.max(2);
+        Self {
+            window,
+            threshold_hpa: threshold_hpa.abs(),
+            readings: VecDeque::with_capacity(window),
+        }
+    }
+
+    pub fn push(&mut self, data: &PressureData) {
+        if self.readings.len() == self.window {
+            self.readings.pop_front();
+        }
+        self.readings.push_back(data.pressure);
+    }
+
+    /// "Rising", "Falling" or "Steady"; "Steady" until two readings arrive.
+    pub fn trend(&self) -> &'static str {
+        if self.readings.len() < 2 {
+            return "Steady";
+        }
+        let half = self.readings.len() / 2;
+        let older = self.readings.iter().take(half).sum::<f32>() / half as f32;
+        let recent =
+            self.readings.iter().skip(half).sum::<f32>() / (self.readings.len() - half) as f32;
+        let change = recent - older;
+        if change > self.threshold_hpa {
+            "Rising"
+        } else if change < -self.threshold_hpa {
+            "Falling"
+        } else {
+            "Steady"
+        }
+    }
+}
+
+/// Aggregate statistics of a recorded route.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RouteSummary {
+    pub fix_count: usize,
+    /// Great-circle distance along the fixes in meters.
+    pub distance_m: f64,
+    /// Time from the first to the last fix in milliseconds.
+    pub duration_ms: i64,
+    /// Fastest speed implied by consecutive fixes, in m/s.
+    pub max_speed_mps: f32,
+}
+
+impl RouteSummary {
+    /// Mean speed over the route in m/s, 0 when no time has elapsed.
+    pub fn average_speed_mps(&self) -> f32 {
+        if self.duration_ms <= 0 {
+            return 0.0;
+        }
+        (self.distance_m / (self.duration_ms as f64 / 1000.0)) as f32
+    }
+}
+
+/// Records a live track and keeps its [`RouteSummary`] up to date in
+/// constant time per fix.
+///
+/// Fixes older than the last accepted one are ignored. Fixes with the same
+/// timestamp add distance but no speed sample.
+#[derive(Debug, Clone, Default)]
+pub struct TrackRecorder {
+    first: Option<GpsData>,
+    last: Option<GpsData>,
+    summary: RouteSummary,
+}
+
+impl TrackRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, fix: GpsData) {
+        let service = SensorService::new();
+        if let Some(last) = &self.last {
+            if fix.timestamp < last.timestamp {
+                return;
+            }
+            self.summary.distance_m += service.distance_between(last, &fix);
+            if let Ok(speed) = service.speed_from_fixes(last, &fix) {
+                self.summary.max_speed_mps = self.summary.max_speed_mps.max(speed);
+            }
+        }
+        let first = self.first.get_or_insert_with(|| fix.clone());
+        self.summary.duration_ms = fix.timestamp - first.timestamp;
+        self.summary.fix_count += 1;
+        self.last = Some(fix);
+    }
+
+    pub fn stats(&self) -> RouteSummary {
+        self.summary.clone()
+    }
+}
+
+/// Shake gesture detection from accelerometer samples.
+///
+/// A swing is a change in acceleration magnitude of more than `threshold`
+/// (m/s²) between consecutive samples. A shake is at least `min_swings`
+/// swings within `window_ms`. It is reported once when it starts; the
+/// detector re-arms only after the swing rate drops below that again, so
+/// one vigorous shake fires once. Samples older than the last one seen are
+/// ignored.
+#[derive(Debug, Clone)]
+pub struct ShakeDetector {
+    threshold: f32,
+    min_swings: usize,
+    window_ms: i64,
+    /// (timestamp, magnitude) of the last sample.
+    previous: Option<(i64, f32)>,
+    /// Timestamps of the swings inside the window, oldest first.
+    swings: VecDeque<i64>,
+    shaking: bool,
+}
+
+impl ShakeDetector {
+    /// `min_swings` is at least 1.
+    pub fn new(threshold: f32, min_swings: usize, window_ms: i64) -> Self {
+        Self {
+            threshold: threshold.abs(),
+            min_swings: min_swings.max(1),
+            window_ms: window_ms.max(0),
+            previous: None,
+            swings: VecDeque::new(),
+            shaking: false,
+        }
+    }
+
+    /// Feeds a sample and returns true when it starts a new shake.
+    pub fn process(&mut self, data: &AccelerometerData) -> bool {
+        if self.previous.is_some_and(|(last, _)| data.timestamp < last) {
+            return false;
+        }
+        let magnitude = math::magnitude(data.x, data.y, data.z);
+        if self
+            .previous
+            .is_some_and(|(_, last)| (magnitude - last).abs() > self.threshold)
+        {
+            self.swings.push_back(data.timestamp);
+        }
+        self.previous = Some((data.timestamp, magnitude));
+        while self
+            .swings
+            .front()
+            .is_some_and(|&t| data.timestamp - t > self.window_ms)
+        {
+            self.swings.pop_front();
+        }
+
+        let active = self.swings.len() >= self.min_swings;
+        let started = active && !self.shaking;
+        self.shaking = active;
+        started
+    }
+}
+
+/// Projects `point` onto a flat east/north plane in meters around `origin`.
+///
+/// Accurate to well under a meter over a few kilometers.
+fn local_xy(origin: &GpsData, point: &GpsData) -> (f64, f64) {
+    let meters_per_degree = EARTH_RADIUS_M.to_radians();
+    let east = (point.longitude - origin.longitude)
+        * meters_per_degree
+        * origin.latitude.to_radians().cos();
+    let north = (point.latitude - origin.latitude) * meters_per_degree;
+    (east, north)
+}
+
+/// Inverse of [`local_xy`], returning `(latitude, longitude)`.
+fn from_local_xy(origin: &GpsData, east: f64, north: f64) -> (f64, f64) {
+    let meters_per_degree = EARTH_RADIUS_M.to_radians();
+    let latitude = origin.latitude + north / meters_per_degree;
+    let longitude =
+        origin.longitude + east / (meters_per_degree * origin.latitude.to_radians().cos());
+    (latitude, longitude)
+}
+
+/// An `io::Write` sink that only counts the bytes written to it.
+struct ByteCounter(usize);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// An `io::Write` sink that feeds everything written into a CRC32 hasher.
+#[cfg(feature = "checksum")]
+struct CrcWriter(crc32fast::Hasher);
+
+#[cfg(feature = "checksum")]
+impl std::io::Write for CrcWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Distance from `p` to the segment `a`-`b` in a planar frame.
+fn segment_distance(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_sq = dx * dx + dy * dy;
+    if length_sq == 0.0 {
+        return (p.0 - a.0).hypot(p.1 - a.1);
+    }
+    let t = (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_sq).clamp(0.0, 1.0);
+    (p.0 - (a.0 + t * dx)).hypot(p.1 - (a.1 + t * dy))
+}
+
+fn finite(value: f32) -> Option<f32> {
+    value.is_finite().then_some(value)
+}
+
+/// Linearly interpolates a sorted accelerometer stream at time `t`, or `None`
+/// when `t` lies outside the stream.
+fn interpolate_accelerometer(samples: &[AccelerometerData], t: i64) -> Option<AccelerometerData> {
+    let after = samples.partition_point(|s| s.timestamp < t);
+    let next = samples.get(after)?;
+    if next.timestamp == t {
+        return Some(next.clone());
+    }
+    let prev = samples.get(after.checked_sub(1)?)?;
+    let fraction = (t - prev.timestamp) as f32 / (next.timestamp - prev.timestamp) as f32;
+    let lerp = |from: f32, to: f32| from + (to - from) * fraction;
+    Some(AccelerometerData {
+        x: lerp(prev.x, next.x),
+        y: lerp(prev.y, next.y),
+        z: lerp(prev.z, next.z),
+        timestamp: t,
+        accuracy: prev.accuracy.min(next.accuracy),
+    })
+}
+
+type Circle = ((f64, f64), f64);
+
+/// Minimum enclosing circle of a non-empty point set (incremental Welzl).
+fn enclosing_circle(points: &[(f64, f64)]) -> Circle {
+    const EPSILON: f64 = 1e-7;
+    let contains = |c: &Circle, p: (f64, f64)| (p.0 - c.0 .0).hypot(p.1 - c.0 .1) <= c.1 + EPSILON;
+
+    let mut circle = (points[0], 0.0);
+    for i in 1..points.len() {
+        if contains(&circle, points[i]) {
+            continue;
+        }
+        circle = (points[i], 0.0);
+        for j in 0..i {
+            if contains(&circle, points[j]) {
+                continue;
+            }
+            circle = circle_from_diameter(points[i], points[j]);
+            for k in 0..j {
+                if !contains(&circle, points[k]) {
+                    circle = circumcircle(points[i], points[j], points[k]);
+                }
+            }
+        }
+    }
+    circle
+}
+
+fn circle_from_diameter(a: (f64, f64), b: (f64, f64)) -> Circle {
+    let center = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+    (center, (a.0 - b.0).hypot(a.1 - b.1) / 2.0)
+}
+
+/// Circle through three points; collinear points fall back to the circle
+/// spanning the farthest pair.
+fn circumcircle(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> Circle {
+    let (bx, by) = (b.0 - a.0, b.1 - a.1);
+    let (cx, cy) = (c.0 - a.0, c.1 - a.1);
+    let d = 2.0 * (bx * cy - by * cx);
+    if d.abs() < 1e-12 {
+        return [(a, b), (a, c), (b, c)]
+            .into_iter()
+            .map(|(p, q)| circle_from_diameter(p, q))
+            .fold((a, 0.0), |best, c| if c.1 > best.1 { c } else { best });
+    }
+    let b2 = bx * bx + by * by;
+    let c2 = cx * cx + cy * cy;
+    let ux = (cy * b2 - by * c2) / d;
+    let uy = (bx * c2 - cx * b2) / d;
+    ((a.0 + ux, a.1 + uy), ux.hypot(uy))
+}
+
+/// Formats `|degrees|` as `D°M'S.s" <hemisphere>`. Rounding happens on whole
+/// tenths of an arcsecond so seconds never read 60.0.
+fn dms(degrees: f64, hemisphere: char) -> String {
+    let tenths = (degrees.abs() * 36_000.0).round() as u64;
+    let (whole_degrees, rest) = (tenths / 36_000, tenths % 36_000);
+    let (minutes, tenths_of_second) = (rest / 600, rest % 600);
+    format!(
+        "{}°{}'{}.{}\" {}",
+        whole_degrees,
+        minutes,
+        tenths_of_second / 10,
+        tenths_of_second % 10,
+        hemisphere
+    )
+}
+
+/// Quotes a CSV field per RFC 4180 when it needs it.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Seconds between the first and last sample, 0 for fewer than two.
+fn window_duration_s(samples: &[AccelerometerData]) -> f32 {
+    match (samples.first(), samples.last()) {
+        (Some(first), Some(last)) => (last.timestamp - first.timestamp) as f32 / 1000.0,
+        _ => 0.0,
+    }
+}
+
+/// Crossings of the mean per second over `duration_s`. Deviations within
+/// 1e-6 of the mean are ignored so a flat signal reads 0.
+fn mean_crossing_rate(values: &[f32], duration_s: f32) -> f32 {
+    const DEAD_BAND: f32 = 1e-6;
+    if values.is_empty() || duration_s <= 0.0 {
+        return 0.0;
+    }
+    let (mean, _) = mean_std(values);
+    let mut previous_sign = None;
+    let mut crossings = 0;
+    for value in values {
+        let deviation = value - mean;
+        if deviation.abs() <= DEAD_BAND {
+            continue;
+        }
+        let sign = deviation > 0.0;
+        if previous_sign.is_some_and(|previous| previous != sign) {
+            crossings += 1;
+        }
+        previous_sign = Some(sign);
+    }
+    crossings as f32 / duration_s
+}
+
+/// Mean and population standard deviation of `values`, or zeros when empty.
+fn mean_std(values: &[f32]) -> (f32, f32) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = values.len() as f32;
+    let mean = values.iter().sum::<f32>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / n;
+    (mean, variance.sqrt())
+}
+
+/// Frequency in Hz of the strongest non-DC component of `values` sampled at
+/// `sample_rate_hz`, or 0 for a flat or too-short signal.
+///
+/// Each candidate frequency is measured with the Goertzel algorithm, so the
+/// cost is O(n) per bin. Every DFT bin up to Nyquist is checked for windows
+/// of up to 512 samples; longer windows are probed at 256 evenly spaced
+/// frequencies, which bounds the cost at O(256·n) and the resolution at
+/// 1/512 of the sample rate.
+fn dominant_frequency(values: &[f32], sample_rate_hz: f32) -> f32 {
+    const MAX_BINS: usize = 256;
+    let n = values.len();
+    if n < 4 || sample_rate_hz <= 0.0 {
+        return 0.0;
+    }
+    let (mean, _) = mean_std(values);
+    let bins = (n / 2).min(MAX_BINS);
+    // Bin index in units of sample_rate / n; fractional past MAX_BINS.
+    let bin_step = (n / 2) as f64 / bins as f64;
+
+    let mut best = (0.0, 1e-9);
+    for bin in 1..=bins {
+        let k = bin as f64 * bin_step;
+        let coefficient = 2.0 * (2.0 * std::f64::consts::PI * k / n as f64).cos();
+        let (mut s1, mut s2) = (0.0_f64, 0.0_f64);
+        for value in values {
+            let s0 = (value - mean) as f64 + coefficient * s1 - s2;
+            s2 = s1;
+            s1 = s0;
+        }
+        let power = s1 * s1 + s2 * s2 - coefficient * s1 * s2;
+        if power > best.1 {
+            best = (k, power);
+        }
+    }
+    (best.0 * sample_rate_hz as f64 / n as f64) as f32
+}
+
+/// The sample whose timestamp is closest to `t`, earliest on a tie.
+fn nearest_by_timestamp<T: SensorReading>(samples: &[T], t: i64) -> Option<&T> {
+    samples
+        .iter()
+        .min_by_key(|s| (s.timestamp() - t).unsigned_abs())
+}
+
+/// UTM zone number (1-60) for a point, including the widened zone 32V over
+/// southwest Norway and the odd-numbered zones over Svalbard.
+fn utm_zone(lat: f64, lon: f64) -> u8 {
+    if (56.0..64.0).contains(&lat) && (3.0..12.0).contains(&lon) {
+        return 32;
+    }
+    if lat >= 72.0 && (0.0..42.0).contains(&lon) {
+        return match lon {
+            l if l < 9.0 => 31,
+            l if l < 21.0 => 33,
+            l if l < 33.0 => 35,
+            _ => 37,
+        };
+    }
+    (((lon + 180.0) / 6.0) as u8 + 1).min(60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks::*;
+
+    #[test]
+    fn test_acceleration_magnitude_at_rest() {
+        let service = SensorService::new();
+        let magnitude = service.calculate_acceleration_magnitude(&mock_accelerometer_at_rest());
+        assert!((magnitude - GRAVITY).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_validate_accelerometer() {
+        let service = SensorService::new();
+        assert!(service
+            .validate_accelerometer(&mock_accelerometer_at_rest())
+            .is_ok());
+
+        let mut bad = mock_accelerometer_at_rest();
+        bad.x = f32::NAN;
+        assert!(matches!(
+            service.validate_accelerometer(&bad),
+            Err(SensorError::DataError(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_gps() {
+        let service = SensorService::new();
+        assert!(service.validate_gps(&mock_gps_san_francisco()).is_ok());
+
+        let mut bad = mock_gps_san_francisco();
+        bad.latitude = 91.0;
+        assert!(service.validate_gps(&bad).is_err());
+
+        let mut bad = mock_gps_san_francisco();
+        bad.longitude = -181.0;
+        assert!(service.validate_gps(&bad).is_err());
+    }
+
+    #[test]
+    fn test_validate_temperature_and_pressure() {
+        let service = SensorService::new();
+        assert!(service
+            .validate_temperature(&mock_temperature_room())
+            .is_ok());
+        assert!(service
+            .validate_pressure(&mock_pressure_sea_level())
+            .is_ok());
+
+        let hot = TemperatureData {
+            temperature: 120.0,
+            timestamp: 0,
+        };
+        assert!(service.validate_temperature(&hot).is_err());
+
+        let low = PressureData {
+            pressure: 100.0,
+            timestamp: 0,
+        };
+        assert!(service.validate_pressure(&low).is_err());
+    }
+
+    #[test]
+    fn test_formatting() {
+        let service = SensorService::new();
+        assert_eq!(
+            service.format_temperature(&mock_temperature_room()),
+            "22.5°C (72.5°F)"
+        );
+        assert_eq!(
+            service.format_pressure(&mock_pressure_sea_level()),
+            "1013.25 hPa"
+        );
+        assert_eq!(
+            service.format_heading(&mock_magnetometer_southwest()),
+            "225° SW"
+        );
+        assert_eq!(
+            service.format_gps(&mock_gps_san_francisco()),
+            "37.774900, -122.419400 (±5.0m)"
+        );
+        assert_eq!(
+            service.format_wifi_network(&mock_wifi_networks()[0]),
+            "HomeNetwork (-45 dBm, 2437 MHz, 2.4 GHz, WPA2)"
+        );
+    }
+
+    #[test]
+    fn test_error_display() {
+        let err = SensorError::NotAvailable("barometer".to_string());
+        assert_eq!(err.to_string(), "Sensor not available: barometer");
+        let err = SensorError::EmptyInput("no samples".to_string());
+        assert_eq!(err.to_string(), "Empty input: no samples");
+    }
+
+    #[test]
+    fn test_to_json_with_units() {
+        let service = SensorService::new();
+        let data = mock_accelerometer_at_rest();
+        let value = service.to_json_with_units(&data);
+
+        for axis in ["x", "y", "z"] {
+            assert_eq!(value[axis]["unit"], "m/s²");
+        }
+        assert_eq!(value["z"]["value"].as_f64().unwrap() as f32, data.z);
+        assert_eq!(value["timestamp"]["unit"], "ms");
+        assert_eq!(value["accuracy"], 3);
+    }
+
+    fn synthetic_walk(
+        steps_per_minute: f32,
+        sample_rate_hz: f32,
+        count: usize,
+    ) -> Vec<AccelerometerData> {
+        let step_hz = steps_per_minute / 60.0;
+        (0..count)
+            .map(|i| {
+                let t = i as f32 / sample_rate_hz;
+                AccelerometerData {
+                    x: 0.0,
+                    y: 0.0,
+                    z: GRAVITY + 2.0 * (2.0 * std::f32::consts::PI * step_hz * t).sin(),
+                    timestamp: (t * 1000.0) as i64,
+                    accuracy: 3,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cadence_of_periodic_signal() {
+        let service = SensorService::new();
+        let samples = synthetic_walk(120.0, 50.0, 500);
+        let cadence = service.cadence(&samples, 50.0).unwrap();
+        assert!((cadence - 120.0).abs() < 3.0, "cadence was {}", cadence);
+    }
+
+    #[test]
+    fn test_cadence_at_rest_is_zero() {
+        let service = SensorService::new();
+        let samples = vec![mock_accelerometer_at_rest(); 200];
+        assert_eq!(service.cadence(&samples, 50.0), Ok(0.0));
+        assert!(matches!(
+            service.cadence(&[], 50.0),
+            Err(SensorError::EmptyInput(_))
+        ));
+        assert!(matches!(
+            service.cadence(&samples[..3], 50.0),
+            Err(SensorError::EmptyInput(_))
+        ));
+        assert!(matches!(
+            service.cadence(&samples, 0.0),
+            Err(SensorError::DataError(_))
+        ));
+    }
+
+    #[test]
+    fn test_normalize_gps_wraps_longitude() {
+        let service = SensorService::new();
+        let mut fix = mock_gps_san_francisco();
+
+        fix.longitude = 190.0;
+        assert!((service.normalize_gps(&fix).longitude + 170.0).abs() < 1e-9);
+
+        fix.longitude = -540.0;
+        assert!((service.normalize_gps(&fix).longitude + 180.0).abs() < 1e-9);
+
+        fix.longitude = 180.0;
+        assert_eq!(service.normalize_gps(&fix).longitude, 180.0);
+    }
+
+    #[test]
+    fn test_normalize_gps_clamps_latitude() {
+        let service = SensorService::new();
+        let mut fix = mock_gps_san_francisco();
+
+        fix.latitude = 95.0;
+        assert_eq!(service.normalize_gps(&fix).latitude, 90.0);
+
+        fix.latitude = -120.0;
+        let normalized = service.normalize_gps(&fix);
+        assert_eq!(normalized.latitude, -90.0);
+        assert!(service.validate_gps(&normalized).is_ok());
+    }
+
+    #[test]
+    fn test_recommend_sample_rate() {
+        let service = SensorService::new();
+
+        let still = vec![mock_accelerometer_at_rest(); 50];
+        let rate = service.recommend_sample_rate(&still, 1.0, 50.0).unwrap();
+        assert!((rate - 1.0).abs() < 0.5, "still rate was {}", rate);
+
+        let moving = synthetic_walk(120.0, 50.0, 100);
+        let rate = service.recommend_sample_rate(&moving, 1.0, 50.0).unwrap();
+        assert!(rate > 45.0, "moving rate was {}", rate);
+
+        for recent in [&[][..], &still[..1]] {
+            assert!(matches!(
+                service.recommend_sample_rate(recent, 1.0, 50.0),
+                Err(SensorError::EmptyInput(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_bandpass_magnitude_attenuates_out_of_band() {
+        let service = SensorService::new();
+        let peak = |output: &[(i64, f32)]| {
+            output[output.len() / 2..]
+                .iter()
+                .map(|(_, v)| v.abs())
+                .fold(0.0_f32, f32::max)
+        };
+
+        let dc = vec![mock_accelerometer_at_rest(); 200];
+        let dc_out = service.bandpass_magnitude(&dc, 0.05, 0.3);
+        assert_eq!(dc_out.len(), dc.len());
+        assert!(peak(&dc_out) < 0.01);
+
+        let jitter: Vec<AccelerometerData> = (0..200)
+            .map(|i| AccelerometerData {
+                z: GRAVITY + if i % 2 == 0 { 2.0 } else { -2.0 },
+                timestamp: i * 20,
+                ..mock_accelerometer_at_rest()
+            })
+            .collect();
+        let jitter_peak = peak(&service.bandpass_magnitude(&jitter, 0.05, 0.3));
+
+        let walk = synthetic_walk(120.0, 50.0, 200);
+        let walk_peak = peak(&service.bandpass_magnitude(&walk, 0.05, 0.3));
+
+        assert!(walk_peak > 1.0, "in-band peak was {}", walk_peak);
+        assert!(
+            jitter_peak < walk_peak / 3.0,
+            "jitter {} vs walk {}",
+            jitter_peak,
+            walk_peak
+        );
+    }
+
+    #[test]
+    fn test_accuracy_bounds_width_matches_accuracy() {
+        let service = SensorService::new();
+        let mut fix = mock_gps_san_francisco();
+        fix.latitude = 45.0;
+        fix.accuracy = 100.0;
+
+        let (min_lat, max_lat, min_lon, max_lon) = service.accuracy_bounds(&fix);
+        let meters_per_degree = EARTH_RADIUS_M.to_radians();
+        let height = (max_lat - min_lat) * meters_per_degree;
+        let width = (max_lon - min_lon) * meters_per_degree * fix.latitude.to_radians().cos();
+
+        assert!((height - 200.0).abs() < 0.1, "height was {}", height);
+        assert!((width - 200.0).abs() < 0.1, "width was {}", width);
+        assert!(max_lon - min_lon > max_lat - min_lat);
+    }
+
+    #[test]
+    fn test_accuracy_bounds_wraps_across_antimeridian() {
+        let service = SensorService::new();
+        for longitude in [179.9999, -179.9999] {
+            let fix = GpsData {
+                latitude: 0.0,
+                longitude,
+                accuracy: 50.0,
+                ..mock_gps_san_francisco()
+            };
+            let (_, _, min_lon, max_lon) = service.accuracy_bounds(&fix);
+            assert!((-180.0..=180.0).contains(&min_lon), "min_lon {min_lon}");
+            assert!((-180.0..=180.0).contains(&max_lon), "max_lon {max_lon}");
+            assert!(min_lon > max_lon, "({min_lon}, {max_lon}) should cross");
+            // 50 m is about 0.00045° at the equator, across the seam.
+            let width = max_lon + 360.0 - min_lon;
+            assert!((width - 0.000_899).abs() < 1e-5, "width {width}");
+        }
+    }
+
+    #[test]
+    fn test_accuracy_bounds_near_pole_spans_all_longitudes() {
+        let service = SensorService::new();
+        let fix = GpsData {
+            latitude: 89.9999,
+            longitude: 170.0,
+            accuracy: 50.0,
+            ..mock_gps_san_francisco()
+        };
+        let (min_lat, max_lat, min_lon, max_lon) = service.accuracy_bounds(&fix);
+        assert_eq!((min_lon, max_lon), (-180.0, 180.0));
+        assert_eq!(max_lat, 90.0);
+        assert!(min_lat < 89.9999);
+
+        let south = GpsData {
+            latitude: -90.0,
+            ..fix
+        };
+        assert_eq!(service.accuracy_bounds(&south).2, -180.0);
+    }
+
+    #[test]
+    fn test_trilaterate_three_aps() {
+        let service = SensorService::new();
+        let target = mock_gps_san_francisco();
+        let offsets = [(30.0, 0.0), (0.0, 40.0), (-25.0, -25.0)];
+        let aps: Vec<(GpsData, f32)> = offsets
+            .iter()
+            .map(|&(east, north)| {
+                let (latitude, longitude) = from_local_xy(&target, east, north);
+                let ap = GpsData {
+                    latitude,
+                    longitude,
+                    ..target.clone()
+                };
+                (ap, (east as f32).hypot(north as f32))
+            })
+            .collect();
+
+        let estimate = service.trilaterate(&aps).unwrap();
+        let (east, north) = local_xy(&target, &estimate);
+        assert!(east.hypot(north) < 1.0, "off by {}, {}", east, north);
+    }
+
+    #[test]
+    fn test_trilaterate_rejects_insufficient_or_degenerate_input() {
+        let service = SensorService::new();
+        let origin = mock_gps_san_francisco();
+        let along_line = |east: f64| {
+            let (latitude, longitude) = from_local_xy(&origin, east, 0.0);
+            GpsData {
+                latitude,
+                longitude,
+                ..origin.clone()
+            }
+        };
+
+        assert!(service
+            .trilaterate(&[(along_line(0.0), 10.0), (along_line(20.0), 10.0)])
+            .is_none());
+        assert!(service
+            .trilaterate(&[
+                (along_line(0.0), 10.0),
+                (along_line(20.0), 10.0),
+                (along_line(40.0), 30.0),
+            ])
+            .is_none());
+    }
+
+    #[test]
+    fn test_validate_temperature_range_custom_bounds() {
+        let service = SensorService::new();
+        let room = mock_temperature_room();
+        assert!(service.validate_temperature(&room).is_ok());
+        assert!(matches!(
+            service.validate_temperature_range(&room, -30.0, -10.0),
+            Err(SensorError::DataError(_))
+        ));
+
+        let freezer = TemperatureData {
+            temperature: -18.0,
+            timestamp: room.timestamp,
+        };
+        assert!(service
+            .validate_temperature_range(&freezer, -30.0, -10.0)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_scan_stability() {
+        let service = SensorService::new();
+        let networks = mock_wifi_networks();
+
+        let stable = vec![networks.clone(); 4];
+        assert!((service.scan_stability(&stable).unwrap() - 1.0).abs() < 1e-6);
+
+        let churning = vec![
+            vec![networks[0].clone()],
+            vec![networks[1].clone()],
+            vec![networks[2].clone()],
+        ];
+        assert_eq!(service.scan_stability(&churning), Ok(0.0));
+
+        let partial = vec![networks.clone(), networks[..2].to_vec()];
+        assert!((service.scan_stability(&partial).unwrap() - 2.0 / 3.0).abs() < 1e-6);
+
+        assert!(matches!(
+            service.scan_stability(&stable[..1]),
+            Err(SensorError::EmptyInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_estimate_json_size_matches_serialized_length() {
+        let service = SensorService::new();
+        let fix = mock_gps_san_francisco();
+        let actual = service.to_json(&fix).unwrap().len();
+        assert_eq!(service.estimate_json_size(&fix).unwrap(), actual);
+
+        let networks = mock_wifi_networks();
+        let expected: usize = networks
+            .iter()
+            .map(|n| service.to_json(n).unwrap().len())
+            .sum();
+        assert_eq!(
+            service.estimate_json_size_batch(&networks).unwrap(),
+            expected
+        );
+        assert_eq!(
+            service
+                .estimate_json_size_batch::<WifiNetwork>(&[])
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_altitude_qnh() {
+        let service = SensorService::new();
+        let sea_level = mock_pressure_sea_level();
+        assert!(service.altitude_qnh(&sea_level, 1013.25).abs() < 0.01);
+
+        let mountain = PressureData {
+            pressure: 899.0,
+            timestamp: 0,
+        };
+        let altitude = service.altitude_qnh(&mountain, 1013.25);
+        assert!(
+            (altitude - 1000.0).abs() < 10.0,
+            "altitude was {}",
+            altitude
+        );
+    }
+
+    #[test]
+    fn test_height_qfe() {
+        let service = SensorService::new();
+        let on_field = PressureData {
+            pressure: 950.0,
+            timestamp: 0,
+        };
+        assert_eq!(service.height_qfe(&on_field, 950.0), 0.0);
+
+        let circuit = PressureData {
+            pressure: 950.0 - 12.0,
+            timestamp: 0,
+        };
+        let height = service.height_qfe(&circuit, 950.0);
+        assert!((height - 107.0).abs() < 5.0, "height was {}", height);
+    }
+
+    fn fix_at(origin: &GpsData, east: f64, north: f64, timestamp: i64) -> GpsData {
+        let (latitude, longitude) = from_local_xy(origin, east, north);
+        GpsData {
+            latitude,
+            longitude,
+            timestamp,
+            ..origin.clone()
+        }
+    }
+
+    #[test]
+    fn test_simplify_track_keeps_corner_and_endpoints() {
+        let service = SensorService::new();
+        let origin = mock_gps_san_francisco();
+        // East along a straight line, then a sharp turn north.
+        let mut track: Vec<GpsData> = (0..=10)
+            .map(|i| fix_at(&origin, i as f64 * 10.0, 0.0, i * 1000))
+            .collect();
+        track.extend((1..=10).map(|i| fix_at(&origin, 100.0, i as f64 * 10.0, (10 + i) * 1000)));
+
+        let simplified = service.simplify_track(&track, 1.0);
+
+        assert_eq!(simplified.len(), 3);
+        assert_eq!(simplified[0], track[0]);
+        assert_eq!(simplified[1], track[10]);
+        assert_eq!(simplified[2], track[20]);
+    }
+
+    #[test]
+    fn test_try_magnitude_rejects_non_finite() {
+        let service = SensorService::new();
+        let at_rest = mock_accelerometer_at_rest();
+        assert!((service.try_magnitude(&at_rest).unwrap() - GRAVITY).abs() < 0.01);
+
+        let mut bad = at_rest.clone();
+        bad.y = f32::NAN;
+        assert_eq!(service.try_magnitude(&bad), None);
+
+        let mut bad = at_rest;
+        bad.z = f32::INFINITY;
+        assert_eq!(service.try_magnitude(&bad), None);
+    }
+
+    #[test]
+    fn test_try_altitude_rejects_invalid_pressure() {
+        let service = SensorService::new();
+        let sea_level = mock_pressure_sea_level();
+        assert!(service.try_altitude_qnh(&sea_level, 1013.25).is_some());
+        assert_eq!(service.try_altitude_qnh(&sea_level, 0.0), None);
+
+        let negative = PressureData {
+            pressure: -5.0,
+            timestamp: 0,
+        };
+        assert_eq!(service.try_height_qfe(&negative, 1000.0), None);
+    }
+
+    #[test]
+    fn test_relative_velocity() {
+        let service = SensorService::new();
+        let mut fix = mock_gps_moving();
+        fix.speed = Some(10.0);
+
+        fix.bearing = Some(90.0);
+        let (forward, lateral) = service.relative_velocity(&fix, 0.0);
+        assert!(forward.abs() < 1e-4);
+        assert!((lateral - 10.0).abs() < 1e-4);
+
+        let (forward, lateral) = service.relative_velocity(&fix, 90.0);
+        assert!((forward - 10.0).abs() < 1e-4);
+        assert!(lateral.abs() < 1e-4);
+
+        fix.bearing = None;
+        assert_eq!(service.relative_velocity(&fix, 30.0), (10.0, 0.0));
+
+        fix.speed = None;
+        assert_eq!(service.relative_velocity(&fix, 30.0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_heading_drift_rate() {
+        let service = SensorService::new();
+        let series = |heading_at: &dyn Fn(usize) -> f32| -> Vec<MagnetometerData> {
+            (0..60)
+                .map(|i| MagnetometerData {
+                    heading: normalize_heading(heading_at(i)),
+                    timestamp: i as i64 * 1000,
+                    ..mock_magnetometer_north()
+                })
+                .collect()
+        };
+
+        // 0.5°/s drift, crossing north partway through.
+        let drifting = series(&|i| 350.0 + 0.5 * i as f32);
+        let rate = service.heading_drift_rate(&drifting).unwrap();
+        assert!((rate - 0.5).abs() < 0.01, "rate was {}", rate);
+
+        let stable = series(&|i| 90.0 + if i % 2 == 0 { 0.3 } else { -0.3 });
+        assert!(service.heading_drift_rate(&stable).unwrap() < 0.01);
+        assert!(matches!(
+            service.heading_drift_rate(&stable[..1]),
+            Err(SensorError::EmptyInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_fixed_point_round_trip() {
+        let service = SensorService::new();
+        let data = mock_accelerometer_moving();
+
+        let encoded = service.to_fixed_point(&data, 1000);
+        assert_eq!(encoded, (1200, 2500, 10400));
+        assert_eq!(
+            service
+                .to_fixed_point(&mock_accelerometer_at_rest(), 1000)
+                .2,
+            9810
+        );
+
+        let decoded = service.from_fixed_point(encoded, 1000, data.timestamp, data.accuracy);
+        for (a, b) in [
+            (data.x, decoded.x),
+            (data.y, decoded.y),
+            (data.z, decoded.z),
+        ] {
+            assert!((a - b).abs() <= 0.0005);
+        }
+        assert_eq!(decoded.timestamp, data.timestamp);
+    }
+
+    #[test]
+    fn test_eta_seconds() {
+        let service = SensorService::new();
+        let mut current = mock_gps_san_francisco();
+        current.speed = Some(5.0);
+        let target = fix_at(&current, 0.0, 1000.0, 0);
+
+        let eta = service.eta_seconds(&current, &target).unwrap();
+        assert!((eta - 200.0).abs() < 1.0, "eta was {}", eta);
+
+        current.speed = Some(0.0);
+        assert_eq!(service.eta_seconds(&current, &target), None);
+        current.speed = None;
+        assert_eq!(service.eta_seconds(&current, &target), None);
+    }
+
+    #[test]
+    fn test_direction_name_styles() {
+        let service = SensorService::new();
+        assert_eq!(
+            service.direction_name(45.0, DirectionStyle::Abbreviated),
+            "NE"
+        );
+        assert_eq!(
+            service.direction_name(45.0, DirectionStyle::Full),
+            "Northeast"
+        );
+        assert_eq!(
+            service.direction_name(315.0, DirectionStyle::Full),
+            "Northwest"
+        );
+        assert_eq!(service.direction_name(359.0, DirectionStyle::Full), "North");
+    }
+
+    #[test]
+    fn test_activity_counts_still_vs_active() {
+        let service = SensorService::new();
+        let mut samples: Vec<AccelerometerData> = (0..50)
+            .map(|i| AccelerometerData {
+                timestamp: i * 20,
+                ..mock_accelerometer_at_rest()
+            })
+            .collect();
+        samples.extend(synthetic_walk(120.0, 50.0, 50).into_iter().map(|mut s| {
+            s.timestamp += 1000;
+            s
+        }));
+
+        let counts = service.activity_counts(&samples, 1000);
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[0].0, 0);
+        assert_eq!(counts[1].0, 1000);
+        assert!(counts[0].1 < 0.01, "still count was {}", counts[0].1);
+        assert!(counts[1].1 > 1.0, "active count was {}", counts[1].1);
+        assert!(service.activity_counts(&[], 1000).is_empty());
+    }
+
+    #[test]
+    fn test_error_is_retryable() {
+        let reason = || "x".to_string();
+        assert!(SensorError::HardwareError(reason()).is_retryable());
+        assert!(SensorError::PluginError(reason()).is_retryable());
+        assert!(!SensorError::NotAvailable(reason()).is_retryable());
+        assert!(!SensorError::PermissionDenied(reason()).is_retryable());
+        assert!(!SensorError::DataError(reason()).is_retryable());
+        assert!(!SensorError::EmptyInput(reason()).is_retryable());
+    }
+
+    #[test]
+    fn test_to_quadrant_bearing() {
+        let service = SensorService::new();
+        assert_eq!(service.to_quadrant_bearing(45.0), "N45°E");
+        assert_eq!(service.to_quadrant_bearing(150.0), "S30°E");
+        assert_eq!(service.to_quadrant_bearing(210.0), "S30°W");
+        assert_eq!(service.to_quadrant_bearing(300.0), "N60°W");
+        assert_eq!(service.to_quadrant_bearing(0.0), "N");
+        assert_eq!(service.to_quadrant_bearing(359.8), "N");
+        assert_eq!(service.to_quadrant_bearing(90.0), "E");
+        assert_eq!(service.to_quadrant_bearing(180.0), "S");
+        assert_eq!(service.to_quadrant_bearing(270.0), "W");
+    }
+
+    #[test]
+    fn test_align_streams_onto_common_grid() {
+        let service = SensorService::new();
+        // Both streams encode x = t / 1000 but are sampled at 50 Hz and 30 Hz
+        // over different ranges.
+        let stream = |period_ms: i64, start: i64, count: i64| -> Vec<AccelerometerData> {
+            (0..count)
+                .map(|i| {
+                    let t = start + i * period_ms;
+                    AccelerometerData {
+                        x: t as f32 / 1000.0,
+                        timestamp: t,
+                        ..mock_accelerometer_at_rest()
+                    }
+                })
+                .collect()
+        };
+        let a = stream(20, 0, 100);
+        let b = stream(33, 500, 60);
+
+        let pairs = service.align_streams(&a, &b, 10.0).unwrap();
+
+        // Overlap is 500 ms to 1980 ms: grid points 500, 600, ..., 1900.
+        assert_eq!(pairs.len(), 15);
+        assert_eq!(pairs[0].0.timestamp, 500);
+        assert_eq!(pairs[14].1.timestamp, 1900);
+        for (sa, sb) in &pairs {
+            assert_eq!(sa.timestamp, sb.timestamp);
+            assert!((sa.x - sb.x).abs() < 1e-4);
+            assert!((sa.x - sa.timestamp as f32 / 1000.0).abs() < 1e-4);
+        }
+
+        assert_eq!(service.align_streams(&a[..10], &b, 10.0), Ok(Vec::new()));
+        for target_hz in [0.0, -5.0, f32::NAN, f32::INFINITY, 1e9] {
+            assert!(matches!(
+                service.align_streams(&a, &b, target_hz),
+                Err(SensorError::DataError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_fuse_heading() {
+        let service = SensorService::new();
+
+        let slow = service.fuse_heading(90.0, Some(120.0), 0.5);
+        assert!((slow - 90.0).abs() < 0.01);
+
+        let fast = service.fuse_heading(90.0, Some(120.0), 10.0);
+        assert!((fast - 117.0).abs() < 0.5, "fast was {}", fast);
+
+        let wrapped = service.fuse_heading(350.0, Some(10.0), 3.0);
+        assert!(
+            heading_difference(wrapped, 0.0).abs() < 10.0,
+            "wrapped was {}",
+            wrapped
+        );
+
+        assert_eq!(service.fuse_heading(45.0, None, 20.0), 45.0);
+    }
+
+    #[test]
+    fn test_session_summary_and_round_trip() {
+        let service = SensorService::new();
+        let mut session = SensorSession::new("pixel-7");
+        session.accelerometer.push(AccelerometerData {
+            timestamp: 1_000,
+            ..mock_accelerometer_at_rest()
+        });
+        session.gps.push(GpsData {
+            timestamp: 2_500,
+            ..mock_gps_san_francisco()
+        });
+        session.update_metadata();
+
+        assert_eq!(
+            service.session_summary(&session),
+            "Session pixel-7: 2 samples (accelerometer 1, gps 1) over 1.5 s"
+        );
+        // Samples pushed without refreshing the header still count.
+        session.pressure.push(PressureData {
+            pressure: 1013.25,
+            timestamp: 4_000,
+        });
+        assert_eq!(
+            service.session_summary(&session),
+            "Session pixel-7: 3 samples (accelerometer 1, gps 1, pressure 1) over 3.0 s"
+        );
+
+        let mut fresh = SensorSession::new("pixel-7");
+        assert_eq!(
+            service.session_summary(&fresh),
+            "Session pixel-7: 0 samples over 0.0 s"
+        );
+        fresh.temperature.push(TemperatureData {
+            temperature: 20.0,
+            timestamp: 1_000,
+        });
+        fresh.temperature.push(TemperatureData {
+            temperature: 20.5,
+            timestamp: 3_000,
+        });
+        assert_eq!(
+            service.session_summary(&fresh),
+            "Session pixel-7: 2 samples (temperature 2) over 2.0 s"
+        );
+
+        let json = service.to_json(&session).unwrap();
+        let parsed: SensorSession = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, session);
+    }
+
+    #[test]
+    fn test_smooth_signal_lags_sudden_changes() {
+        let service = SensorService::new();
+        let networks = mock_wifi_networks();
+        let home = &networks[0];
+        let reading = |t: i64, signal: i32| {
+            (
+                t,
+                WifiNetwork {
+                    signal_strength: signal,
+                    ..home.clone()
+                },
+            )
+        };
+        let history = vec![
+            reading(0, -50),
+            (0, networks[1].clone()),
+            reading(1000, -50),
+            reading(2000, -50),
+            reading(3000, -80),
+        ];
+
+        let smoothed = service.smooth_signal(&history, &home.bssid, 0.3).unwrap();
+        assert_eq!(smoothed, -59);
+        assert_eq!(service.smooth_signal(&history, &home.bssid, 1.0), Some(-80));
+        assert_eq!(service.smooth_signal(&history, "no:such:ap", 0.3), None);
+    }
+
+    #[test]
+    fn test_format_true_heading() {
+        let service = SensorService::new();
+        let mut data = mock_magnetometer_north();
+        data.heading = 10.0;
+        assert_eq!(service.format_true_heading(&data, -13.0), "357° N");
+        assert_eq!(
+            service.format_true_heading(&mock_magnetometer_southwest(), 90.0),
+            "315° NW"
+        );
+    }
+
+    #[test]
+    fn test_dedup_by_timestamp_keeps_last() {
+        let service = SensorService::new();
+        let reading = |temperature: f32, timestamp: i64| TemperatureData {
+            temperature,
+            timestamp,
+        };
+        let samples = vec![
+            reading(20.0, 1000),
+            reading(21.0, 2000),
+            reading(21.5, 2000),
+            reading(22.0, 3000),
+            reading(22.1, 3000),
+            reading(22.2, 3000),
+        ];
+
+        let deduped = service.dedup_by_timestamp(&samples);
+        let values: Vec<f32> = deduped.iter().map(|r| r.temperature).collect();
+        assert_eq!(values, vec![20.0, 21.5, 22.2]);
+        assert_eq!(service.dedup_by_timestamp::<GpsData>(&[]), vec![]);
+    }
+
+    #[test]
+    fn test_planar_magnitude() {
+        let service = SensorService::new();
+        let data = AccelerometerData {
+            x: 3.0,
+            y: 4.0,
+            z: 12.0,
+            ..mock_accelerometer_at_rest()
+        };
+        assert_eq!(
+            service.planar_magnitude(&data, Axis::X),
+            4.0_f32.hypot(12.0)
+        );
+        assert_eq!(
+            service.planar_magnitude(&data, Axis::Y),
+            3.0_f32.hypot(12.0)
+        );
+        assert_eq!(service.planar_magnitude(&data, Axis::Z), 5.0);
+
+        let upright = AccelerometerData {
+            x: 0.0,
+            y: GRAVITY,
+            z: 0.0,
+            ..mock_accelerometer_at_rest()
+        };
+        assert!(service.planar_magnitude(&upright, Axis::Y) < 1e-6);
+    }
+
+    #[test]
+    fn test_distance_between_known_cities() {
+        let service = SensorService::new();
+        let sf = mock_gps_san_francisco();
+        let la = GpsData {
+            latitude: 34.0522,
+            longitude: -118.2437,
+            ..sf.clone()
+        };
+        let distance = service.distance_between(&sf, &la);
+        assert!(
+            (distance - 559_000.0).abs() < 5_000.0,
+            "distance was {}",
+            distance
+        );
+
+        let mut higher = sf.clone();
+        higher.altitude = Some(1000.0);
+        assert_eq!(service.distance_between(&sf, &higher), 0.0);
+
+        let moving = mock_gps_moving();
+        assert!((service.distance_between(&sf, &moving) - 1_420.0).abs() < 10.0);
+    }
+
+    #[test]
+    fn test_bearing_between() {
+        let service = SensorService::new();
+        let origin = mock_gps_san_francisco();
+        let north = fix_at(&origin, 0.0, 1000.0, 0);
+        let east = fix_at(&origin, 1000.0, 0.0, 0);
+
+        let bearing = service.bearing_between(&origin, &north);
+        assert!(
+            heading_difference(bearing, 0.0).abs() < 0.1,
+            "bearing was {}",
+            bearing
+        );
+        assert!((service.bearing_between(&origin, &east) - 90.0).abs() < 0.1);
+        assert!((service.bearing_between(&north, &origin) - 180.0).abs() < 0.1);
+
+        assert_eq!(service.format_bearing(&origin, &east), "90° E");
+    }
+
+    #[test]
+    fn test_grade_percent() {
+        let service = SensorService::new();
+        let start = mock_gps_san_francisco();
+        let mut end = fix_at(&start, 0.0, 100.0, 0);
+
+        end.altitude = start.altitude.map(|a| a + 10.0);
+        assert!((service.grade_percent(&start, &end).unwrap() - 10.0).abs() < 0.01);
+
+        end.altitude = start.altitude.map(|a| a - 5.0);
+        assert!((service.grade_percent(&start, &end).unwrap() + 5.0).abs() < 0.01);
+
+        end.altitude = start.altitude;
+        assert_eq!(service.grade_percent(&start, &end), Some(0.0));
+
+        end.altitude = None;
+        assert_eq!(service.grade_percent(&start, &end), None);
+        assert_eq!(service.grade_percent(&start, &start), None);
+    }
+
+    #[test]
+    fn test_speed_from_fixes() {
+        let service = SensorService::new();
+        let start = GpsData {
+            timestamp: 10_000,
+            ..mock_gps_san_francisco()
+        };
+        let end = fix_at(&start, 300.0, 400.0, 60_000);
+
+        let speed = service.speed_from_fixes(&start, &end).unwrap();
+        assert!((speed - 10.0).abs() < 0.05, "speed was {}", speed);
+
+        assert!(matches!(
+            service.speed_from_fixes(&start, &start),
+            Err(SensorError::DataError(_))
+        ));
+        assert!(matches!(
+            service.speed_from_fixes(&end, &start),
+            Err(SensorError::DataError(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_humidity() {
+        let service = SensorService::new();
+        assert!(service
+            .validate_humidity(&mock_humidity_comfortable())
+            .is_ok());
+        for humidity in [-1.0, 100.5] {
+            let data = HumidityData {
+                humidity,
+                timestamp: 0,
+            };
+            assert!(matches!(
+                service.validate_humidity(&data),
+                Err(SensorError::DataError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_format_humidity_bands() {
+        let service = SensorService::new();
+        let at = |humidity| HumidityData {
+            humidity,
+            timestamp: 0,
+        };
+        assert_eq!(
+            service.format_humidity(&mock_humidity_comfortable()),
+            "45.0% (Comfortable)"
+        );
+        assert_eq!(service.format_humidity(&at(20.0)), "20.0% (Dry)");
+        assert_eq!(service.format_humidity(&at(75.0)), "75.0% (Humid)");
+    }
+
+    #[test]
+    fn test_temperature_compensate_pressure() {
+        let service = SensorService::new();
+        let pressure = mock_pressure_sea_level();
+        let at = |temperature| TemperatureData {
+            temperature,
+            timestamp: 0,
+        };
+
+        let unchanged = service.temperature_compensate_pressure(&pressure, &at(25.0), 0.05, 25.0);
+        assert_eq!(unchanged, pressure);
+
+        let warm = service.temperature_compensate_pressure(&pressure, &at(35.0), 0.05, 25.0);
+        let hot = service.temperature_compensate_pressure(&pressure, &at(45.0), 0.05, 25.0);
+        assert!((pressure.pressure - warm.pressure - 0.5).abs() < 1e-3);
+        assert!((pressure.pressure - hot.pressure - 1.0).abs() < 1e-3);
+
+        let cold = service.temperature_compensate_pressure(&pressure, &at(15.0), 0.05, 25.0);
+        assert!((cold.pressure - pressure.pressure - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_dew_point_against_reference_table() {
+        let service = SensorService::new();
+        let at = |temperature| TemperatureData {
+            temperature,
+            timestamp: 0,
+        };
+        for (temperature, humidity, expected) in [
+            (25.0, 60.0, 16.7),
+            (20.0, 50.0, 9.3),
+            (30.0, 80.0, 26.2),
+            (10.0, 100.0, 10.0),
+        ] {
+            let dew_point = service.dew_point(&at(temperature), humidity).unwrap();
+            assert!(
+                (dew_point - expected).abs() < 0.5,
+                "{}°C at {}%: {}",
+                temperature,
+                humidity,
+                dew_point
+            );
+        }
+
+        assert!(matches!(
+            service.dew_point(&at(25.0), 0.0),
+            Err(SensorError::DataError(_))
+        ));
+        assert!(service.dew_point(&at(25.0), 101.0).is_err());
+    }
+
+    #[test]
+    fn test_segment_track_splits_on_gap() {
+        let service = SensorService::new();
+        let origin = mock_gps_san_francisco();
+        let mut track: Vec<GpsData> = (0..5)
+            .map(|i| fix_at(&origin, i as f64 * 10.0, 0.0, i * 1000))
+            .collect();
+        track.extend((0..3).map(|i| fix_at(&origin, 0.0, i as f64 * 10.0, 600_000 + i * 1000)));
+
+        let segments = service.segment_track(&track, 60_000);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0], track[..5]);
+        assert_eq!(segments[1], track[5..]);
+
+        assert_eq!(service.segment_track(&track, 1_000_000).len(), 1);
+        assert!(service.segment_track(&[], 1000).is_empty());
+    }
+
+    #[test]
+    fn test_heat_index() {
+        let service = SensorService::new();
+        let at_f = |fahrenheit: f32| TemperatureData {
+            temperature: (fahrenheit - 32.0) * 5.0 / 9.0,
+            timestamp: 0,
+        };
+        let to_f = |celsius: f32| celsius * 9.0 / 5.0 + 32.0;
+
+        // NOAA heat index chart reference points.
+        let hi = to_f(service.heat_index(&at_f(90.0), 70.0));
+        assert!((hi - 106.0).abs() < 1.5, "90°F/70%: {}", hi);
+        let hi = to_f(service.heat_index(&at_f(86.0), 60.0));
+        assert!((hi - 91.0).abs() < 1.5, "86°F/60%: {}", hi);
+
+        let cool = mock_temperature_room();
+        assert_eq!(service.heat_index(&cool, 90.0), cool.temperature);
+
+        let hot = at_f(95.0);
+        assert_eq!(
+            service.heat_index(&hot, 150.0),
+            service.heat_index(&hot, 100.0)
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_position_weights_stronger_ap() {
+        let service = SensorService::new();
+        let origin = mock_gps_san_francisco();
+        let networks = mock_wifi_networks();
+        let ap_locations: HashMap<String, GpsData> = [
+            (networks[0].bssid.clone(), fix_at(&origin, 0.0, 0.0, 0)),
+            (networks[1].bssid.clone(), fix_at(&origin, 100.0, 0.0, 0)),
+        ]
+        .into_iter()
+        .collect();
+
+        let observed = vec![
+            WifiNetwork {
+                signal_strength: -40,
+                ..networks[0].clone()
+            },
+            WifiNetwork {
+                signal_strength: -60,
+                ..networks[1].clone()
+            },
+            networks[2].clone(),
+        ];
+        let position = service
+            .fingerprint_position(&observed, &ap_locations)
+            .unwrap();
+        let (east, north) = local_xy(&origin, &position);
+        // Weights 10^-2 and 10^-3: one eleventh of the way to the weaker AP.
+        assert!((east - 100.0 / 11.0).abs() < 0.1, "east was {}", east);
+        assert!(north.abs() < 0.1);
+
+        assert!(service
+            .fingerprint_position(&networks[2..], &ap_locations)
+            .is_none());
+    }
+
+    #[test]
+    fn test_rolling_stats_known_values() {
+        let mut stats = RollingStats::new(8);
+        for value in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+            stats.push(value);
+        }
+        assert_eq!(stats.len(), 8);
+        assert_eq!(stats.mean(), Some(5.0));
+        assert_eq!(stats.min(), Some(2.0));
+        assert_eq!(stats.max(), Some(9.0));
+        assert_eq!(stats.std_dev(), Some(2.0));
+    }
+
+    #[test]
+    fn test_rolling_stats_evicts_oldest() {
+        let mut stats = RollingStats::new(3);
+        for value in [100.0, 1.0, 2.0, 3.0] {
+            stats.push(value);
+        }
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats.max(), Some(3.0));
+        assert_eq!(stats.mean(), Some(2.0));
+    }
+
+    #[test]
+    fn test_rolling_stats_empty() {
+        let stats = RollingStats::new(5);
+        assert!(stats.is_empty());
+        assert_eq!(stats.mean(), None);
+        assert_eq!(stats.min(), None);
+        assert_eq!(stats.max(), None);
+        assert_eq!(stats.std_dev(), None);
+    }
+
+    #[test]
+    fn test_is_steady_for_capture() {
+        let service = SensorService::new();
+        let gyro = |rate: f32| -> Vec<GyroscopeData> {
+            (0..20)
+                .map(|i| GyroscopeData {
+                    x: if i % 2 == 0 { rate } else { -rate },
+                    y: 0.0,
+                    z: 0.0,
+                    timestamp: i * 20,
+                    accuracy: 3,
+                })
+                .collect()
+        };
+        let still = vec![mock_accelerometer_at_rest(); 20];
+        let shaky = synthetic_walk(120.0, 50.0, 20);
+
+        assert!(service.is_steady_for_capture(&still, &gyro(0.01), 0.1, 0.05));
+        assert!(!service.is_steady_for_capture(&shaky, &gyro(0.01), 0.1, 0.05));
+        assert!(!service.is_steady_for_capture(&still, &gyro(0.5), 0.1, 0.05));
+        assert!(!service.is_steady_for_capture(&still, &[], 0.1, 0.05));
+    }
+
+    #[test]
+    fn test_step_detector_counts_synthetic_walk() {
+        // 120 steps/min for 10 s at 50 Hz: one peak every 500 ms.
+        let samples = synthetic_walk(120.0, 50.0, 500);
+
+        let mut detector = StepDetector::new(11.0, 300);
+        let detections = samples.iter().filter(|s| detector.process(s)).count();
+        assert_eq!(detector.step_count(), 20);
+        assert_eq!(detections, 20);
+
+        // A refractory period longer than the step interval skips every other peak.
+        let mut debounced = StepDetector::new(11.0, 600);
+        samples.iter().for_each(|s| {
+            debounced.process(s);
+        });
+        assert_eq!(debounced.step_count(), 10);
+    }
+
+    #[test]
+    fn test_step_detector_ignores_out_of_order_and_weak_peaks() {
+        let samples = synthetic_walk(120.0, 50.0, 50);
+
+        let mut detector = StepDetector::new(12.0, 300);
+        samples.iter().for_each(|s| {
+            detector.process(s);
+        });
+        assert_eq!(detector.step_count(), 0);
+
+        let mut detector = StepDetector::new(11.0, 300);
+        for sample in &samples[..10] {
+            detector.process(sample);
+        }
+        assert_eq!(detector.step_count(), 1);
+        let mut stale = samples[7].clone();
+        stale.z = 20.0;
+        assert!(!detector.process(&stale));
+        assert!(!detector.process(&samples[10]));
+        assert_eq!(detector.step_count(), 1);
+    }
+
+    #[test]
+    fn test_heading_to_json() {
+        let service = SensorService::new();
+        let json = service.heading_to_json(&mock_magnetometer_southwest());
+        assert_eq!(json["degrees"].as_f64(), Some(225.0));
+        assert_eq!(json["cardinal"], "SW");
+    }
+
+    #[test]
+    fn test_bounding_circle_encloses_cluster() {
+        let service = SensorService::new();
+        let origin = mock_gps_san_francisco();
+        let cluster: Vec<GpsData> = [
+            (0.0, 0.0),
+            (40.0, 0.0),
+            (0.0, 30.0),
+            (10.0, 10.0),
+            (35.0, 20.0),
+            (-5.0, 12.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, &(e, n))| fix_at(&origin, e, n, i as i64))
+        .collect();
+
+        let (center, radius) = service.bounding_circle(&cluster).unwrap();
+        for fix in &cluster {
+            assert!(service.distance_between(&center, fix) <= radius + 0.01);
+        }
+        // (0,0), (40,0) and (0,30) form a right triangle: hypotenuse 50 m.
+        assert!((radius - 25.0).abs() < 0.05, "radius {radius}");
+        assert_eq!(center.timestamp, 5);
+    }
+
+    #[test]
+    fn test_bounding_circle_degenerate_inputs() {
+        let service = SensorService::new();
+        assert!(service.bounding_circle(&[]).is_none());
+
+        let fix = mock_gps_san_francisco();
+        let (center, radius) = service.bounding_circle(std::slice::from_ref(&fix)).unwrap();
+        assert_eq!(radius, 0.0);
+        assert!((center.latitude - fix.latitude).abs() < 1e-9);
+        assert!((center.longitude - fix.longitude).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_orientation_angles() {
+        let service = SensorService::new();
+
+        let (pitch, roll) = service.orientation_angles(&mock_accelerometer_at_rest());
+        assert!(pitch.abs() < 0.01 && roll.abs() < 0.01);
+
+        let on_side = AccelerometerData {
+            y: GRAVITY,
+            z: 0.0,
+            ..mock_accelerometer_at_rest()
+        };
+        let (pitch, roll) = service.orientation_angles(&on_side);
+        assert!(pitch.abs() < 0.01);
+        assert!((roll - 90.0).abs() < 0.01);
+
+        let zero = AccelerometerData {
+            z: 0.0,
+            ..mock_accelerometer_at_rest()
+        };
+        assert_eq!(service.orientation_angles(&zero), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_is_free_fall() {
+        let service = SensorService::new();
+        assert!(!service.is_free_fall(&mock_accelerometer_at_rest(), 2.0));
+
+        let falling = AccelerometerData {
+            x: 0.1,
+            y: -0.2,
+            z: 0.3,
+            ..mock_accelerometer_at_rest()
+        };
+        assert!(service.is_free_fall(&falling, 2.0));
+    }
+
+    #[test]
+    fn test_merge_scans_with_strategies() {
+        let service = SensorService::new();
+        let first = mock_wifi_networks();
+        let mut second = mock_wifi_networks();
+        second[0].signal_strength = -55;
+        second[1].signal_strength = -61;
+        second.remove(2);
+        let scans = vec![first, second];
+
+        let strongest = service.merge_scans_with(&scans, MergeStrategy::Strongest);
+        let average = service.merge_scans_with(&scans, MergeStrategy::Average);
+        let latest = service.merge_scans_with(&scans, MergeStrategy::Latest);
+
+        let signals =
+            |merged: &[WifiNetwork]| merged.iter().map(|n| n.signal_strength).collect::<Vec<_>>();
+        assert_eq!(signals(&strongest), vec![-45, -61, -80]);
+        assert_eq!(signals(&average), vec![-50, -64, -80]);
+        assert_eq!(signals(&latest), vec![-55, -61, -80]);
+        assert_eq!(strongest[0].ssid, "HomeNetwork");
+        assert!(service
+            .merge_scans_with(&[], MergeStrategy::Average)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_wifi_channel() {
+        let service = SensorService::new();
+        let on = |frequency: u32| WifiNetwork {
+            frequency,
+            ..mock_wifi_networks()[0].clone()
+        };
+        assert_eq!(service.wifi_channel(&on(2412)), Some(1));
+        assert_eq!(service.wifi_channel(&on(2437)), Some(6));
+        assert_eq!(service.wifi_channel(&on(2484)), Some(14));
+        assert_eq!(service.wifi_channel(&on(5180)), Some(36));
+        assert_eq!(service.wifi_channel(&on(5825)), Some(165));
+        assert_eq!(service.wifi_channel(&on(5745)), Some(149));
+        assert_eq!(service.wifi_channel(&on(5955)), Some(1));
+        assert_eq!(service.wifi_channel(&on(2413)), None);
+        assert_eq!(service.wifi_channel(&on(900)), None);
+        // 5 MHz steps between 20 MHz channel centers.
+        for off_grid in [5185, 5190, 5750, 5960] {
+            assert_eq!(service.wifi_channel(&on(off_grid)), None, "{off_grid} MHz");
+        }
+    }
+
+    #[test]
+    fn test_vibration_dose_value() {
+        let service = SensorService::new();
+        // A·sin over whole cycles: ∫ a⁴ dt = 3/8 · A⁴ · T, so with A = 2 m/s²
+        // and T = 10 s the VDV is 2 · 3.75^(1/4) ≈ 2.783.
+        let samples = synthetic_walk(120.0, 50.0, 500);
+        let vdv = service.vibration_dose_value(&samples, 50.0).unwrap();
+        assert!((vdv - 2.0 * 3.75f32.powf(0.25)).abs() < 0.01, "vdv {vdv}");
+
+        let still = vec![mock_accelerometer_at_rest(); 100];
+        assert!(service.vibration_dose_value(&still, 50.0).unwrap() < 1e-3);
+        assert!(matches!(
+            service.vibration_dose_value(&[], 50.0),
+            Err(SensorError::EmptyInput(_))
+        ));
+        assert!(matches!(
+            service.vibration_dose_value(&samples, 0.0),
+            Err(SensorError::DataError(_))
+        ));
+    }
+
+    #[test]
+    fn test_wifi_band() {
+        let service = SensorService::new();
+        let on = |frequency: u32| WifiNetwork {
+            frequency,
+            ..mock_wifi_networks()[0].clone()
+        };
+        assert_eq!(service.wifi_band(&on(2437)), "2.4 GHz");
+        assert_eq!(service.wifi_band(&on(5180)), "5 GHz");
+        assert_eq!(service.wifi_band(&on(5955)), "6 GHz");
+        assert_eq!(service.wifi_band(&on(5910)), "Unknown");
+        assert_eq!(service.wifi_band(&on(900)), "Unknown");
+        assert_eq!(
+            service.format_wifi_network(&mock_wifi_networks()[1]),
+            "Neighbor_5G (-67 dBm, 5180 MHz, 5 GHz, WPA3)"
+        );
+    }
+
+    #[test]
+    fn test_to_enu() {
+        let service = SensorService::new();
+        let origin = mock_gps_san_francisco();
+        assert_eq!(service.to_enu(&origin, &origin), (0.0, 0.0, 0.0));
+
+        let mut east = fix_at(&origin, 100.0, 0.0, 0);
+        east.altitude = Some(21.0);
+        let (e, n, u) = service.to_enu(&origin, &east);
+        assert!((e - 100.0).abs() < 0.01);
+        assert!(n.abs() < 0.01);
+        assert!((u - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_turn_detector_ignores_drift() {
+        // 2°/s for a minute: 120° in total but never 45° within 5 s.
+        let mut detector = TurnDetector::new(45.0, 5_000);
+        let turns: Vec<_> = (0..60)
+            .filter_map(|s| detector.update(s as f32 * 2.0, s * 1_000))
+            .collect();
+        assert!(turns.is_empty());
+    }
+
+    #[test]
+    fn test_turn_detector_detects_sharp_turns() {
+        let mut detector = TurnDetector::new(45.0, 5_000);
+        let headings = [350.0, 350.0, 20.0, 80.0, 80.0, 80.0, 80.0, 80.0, 80.0, 10.0];
+        let turns: Vec<_> = headings
+            .iter()
+            .enumerate()
+            .filter_map(|(s, &h)| detector.update(h, s as i64 * 1_000).map(|d| (s, d)))
+            .collect();
+        assert_eq!(
+            turns,
+            vec![(3, TurnDirection::Right), (9, TurnDirection::Left)]
+        );
+
+        // A second sharp turn inside the interval is suppressed.
+        let mut detector = TurnDetector::new(45.0, 5_000);
+        detector.update(0.0, 0);
+        assert_eq!(detector.update(90.0, 1_000), Some(TurnDirection::Right));
+        assert_eq!(detector.update(0.0, 2_000), None);
+    }
+
+    #[test]
+    fn test_estimate_wifi_distance() {
+        let service = SensorService::new();
+        let networks = mock_wifi_networks();
+        let distances: Vec<f64> = networks
+            .iter()
+            .map(|n| service.estimate_wifi_distance(n, -40))
+            .collect();
+        assert!(distances.windows(2).all(|pair| pair[0] < pair[1]));
+        // 20 dB below the 1 m reference is 10 m in free space.
+        let at_ten_meters = WifiNetwork {
+            signal_strength: -60,
+            ..networks[0].clone()
+        };
+        assert!((service.estimate_wifi_distance(&at_ten_meters, -40) - 10.0).abs() < 1e-9);
+
+        let no_reading = WifiNetwork {
+            signal_strength: 0,
+            ..networks[0].clone()
+        };
+        assert_eq!(
+            service.estimate_wifi_distance(&no_reading, -40),
+            f64::INFINITY
+        );
+    }
+
+    #[test]
+    fn test_temperature_conversions() {
+        let service = SensorService::new();
+        assert_eq!(service.to_fahrenheit(0.0), 32.0);
+        assert_eq!(service.to_fahrenheit(100.0), 212.0);
+        assert!((service.to_kelvin(0.0).unwrap() - 273.15).abs() < 1e-4);
+        assert!(service.to_celsius_from_kelvin(273.15).unwrap().abs() < 1e-4);
+
+        for celsius in [-40.0, 0.0, 22.5, 100.0] {
+            let kelvin = service.to_kelvin(celsius).unwrap();
+            let back = service.to_celsius_from_kelvin(kelvin).unwrap();
+            assert!((back - celsius).abs() < 1e-3);
+        }
+
+        assert!(service.to_kelvin(-300.0).is_err());
+        assert!(service.to_celsius_from_kelvin(-1.0).is_err());
+        assert_eq!(
+            service
+                .format_temperature_full(&mock_temperature_room())
+                .unwrap(),
+            "22.5°C (72.5°F, 295.65 K)"
+        );
+    }
+
+    #[test]
+    fn test_pressure_unit_conversions() {
+        let service = SensorService::new();
+        let standard = mock_pressure_sea_level();
+        assert!((service.pressure_in_inhg(&standard) - 29.92).abs() < 0.01);
+        assert!((service.pressure_in_mmhg(&standard) - 760.0).abs() < 0.1);
+        assert!((service.pressure_in_psi(&standard) - 14.696).abs() < 0.01);
+        assert_eq!(
+            service.format_pressure_multi(&standard),
+            "1013.25 hPa (29.92 inHg)"
+        );
+    }
+
+    #[test]
+    fn test_zero_crossing_rate() {
+        let service = SensorService::new();
+        // A 2 Hz oscillation crosses its mean four times per second.
+        let walk = synthetic_walk(120.0, 50.0, 500);
+        let rate = service.zero_crossing_rate(&walk, Axis::Z);
+        assert!((rate - 4.0).abs() < 0.15, "rate {rate}");
+        assert_eq!(service.zero_crossing_rate(&walk, Axis::X), 0.0);
+
+        let still: Vec<AccelerometerData> = (0..100)
+            .map(|i| AccelerometerData {
+                timestamp: i * 20,
+                ..mock_accelerometer_moving()
+            })
+            .collect();
+        assert_eq!(service.zero_crossing_rate(&still, Axis::Y), 0.0);
+        assert_eq!(service.zero_crossing_rate(&[], Axis::Z), 0.0);
+    }
+
+    #[test]
+    fn test_speed_conversions() {
+        let service = SensorService::new();
+        let moving = mock_gps_moving();
+        assert!((service.speed_kmh(&moving).unwrap() - 48.24).abs() < 0.01);
+        assert!((service.speed_mph(&moving).unwrap() - 29.98).abs() < 0.01);
+        assert_eq!(service.format_speed(&moving), "48.2 km/h (30.0 mph)");
+
+        let unknown = GpsData {
+            speed: None,
+            ..moving
+        };
+        assert_eq!(service.speed_kmh(&unknown), None);
+        assert_eq!(service.speed_mph(&unknown), None);
+        assert_eq!(service.format_speed(&unknown), "N/A");
+    }
+
+    #[test]
+    fn test_available_metrics() {
+        let service = SensorService::new();
+        let metrics = service.available_metrics();
+        for name in [
+            "cadence",
+            "distance_between",
+            "dew_point",
+            "wifi_band",
+            "to_celsius_from_kelvin",
+            "pressure_in_psi",
+        ] {
+            assert!(metrics.contains(&name), "missing {name}");
+        }
+        let unique: HashSet<_> = metrics.iter().collect();
+        assert_eq!(unique.len(), metrics.len());
+        for name in ["format_gps", "simplify_track", "to_fixed_point"] {
+            assert!(!metrics.contains(&name), "{name} is not a metric");
+        }
+    }
+
+    #[test]
+    fn test_format_gps_dms() {
+        let service = SensorService::new();
+        assert_eq!(
+            service.format_gps_dms(&mock_gps_san_francisco()),
+            "37°46'29.6\" N, 122°25'9.8\" W"
+        );
+
+        let southeast = GpsData {
+            latitude: -33.8688,
+            longitude: 151.2093,
+            ..mock_gps_san_francisco()
+        };
+        assert_eq!(
+            service.format_gps_dms(&southeast),
+            "33°52'7.7\" S, 151°12'33.5\" E"
+        );
+
+        // 59.99" rounds up into the next minute and degree, never to 60.0".
+        let edge = GpsData {
+            latitude: 89.999_999_9,
+            longitude: -179.999_999_9,
+            ..mock_gps_san_francisco()
+        };
+        assert_eq!(service.format_gps_dms(&edge), "90°0'0.0\" N, 180°0'0.0\" W");
+    }
+
+    #[test]
+    fn test_cross_track_distance() {
+        let service = SensorService::new();
+        let origin = mock_gps_san_francisco();
+        let end = fix_at(&origin, 0.0, 1_000.0, 0);
+
+        let on_path = fix_at(&origin, 0.0, 400.0, 0);
+        assert!(service.cross_track_distance(&on_path, &origin, &end).abs() < 0.01);
+
+        let east = fix_at(&origin, 25.0, 400.0, 0);
+        let west = fix_at(&origin, -25.0, 400.0, 0);
+        assert!((service.cross_track_distance(&east, &origin, &end) - 25.0).abs() < 0.1);
+        assert!((service.cross_track_distance(&west, &origin, &end) + 25.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_gps_to_geojson() {
+        let service = SensorService::new();
+        let parsed: Value =
+            serde_json::from_str(&service.gps_to_geojson(&mock_gps_san_francisco())).unwrap();
+        assert_eq!(parsed["type"], "Feature");
+        assert_eq!(parsed["geometry"]["type"], "Point");
+        assert_eq!(
+            parsed["geometry"]["coordinates"],
+            json!([-122.4194, 37.7749])
+        );
+        assert_eq!(parsed["properties"]["altitude"], json!(16.0));
+        assert_eq!(parsed["properties"]["accuracy"], json!(5.0));
+
+        let bare = GpsData {
+            altitude: None,
+            speed: None,
+            ..mock_gps_san_francisco()
+        };
+        let parsed: Value = serde_json::from_str(&service.gps_to_geojson(&bare)).unwrap();
+        let properties = parsed["properties"].as_object().unwrap();
+        assert!(!properties.contains_key("altitude"));
+        assert!(!properties.contains_key("speed"));
+        assert_eq!(properties["timestamp"], json!(bare.timestamp));
+    }
+
+    #[test]
+    fn test_elevation_profile_reduces_noise_gain() {
+        let service = SensorService::new();
+        // Flat ground with ±1 m of alternating altitude noise, and one fix
+        // without altitude.
+        let mut fixes: Vec<GpsData> = (0..40)
+            .map(|i| GpsData {
+                altitude: Some(if i % 2 == 0 { 100.0 } else { 102.0 }),
+                timestamp: i * 1_000,
+                ..mock_gps_san_francisco()
+            })
+            .collect();
+        fixes[5].altitude = None;
+        let gain = |profile: &[(i64, f64)]| -> f64 {
+            profile
+                .windows(2)
+                .map(|pair| (pair[1].1 - pair[0].1).max(0.0))
+                .sum()
+        };
+
+        let raw = service.elevation_profile(&fixes, 1);
+        let smoothed = service.elevation_profile(&fixes, 4);
+        assert_eq!(raw.len(), 39);
+        assert_eq!(smoothed.len(), 39);
+        assert!(raw.iter().all(|&(t, _)| t != 5_000));
+        assert!(gain(&raw) > 30.0);
+        assert!(gain(&smoothed) < 5.0, "gain {}", gain(&smoothed));
+    }
+
+    #[test]
+    fn test_wifi_6ghz_support() {
+        let service = SensorService::new();
+        let on = |frequency: u32| WifiNetwork {
+            frequency,
+            ..mock_wifi_networks()[0].clone()
+        };
+        let six = on(5975);
+        assert_eq!(service.classify_band(&six), Some(WifiBand::Band6GHz));
+        assert_eq!(service.wifi_channel(&six), Some(5));
+        assert_eq!(service.wifi_channel(&on(5955)), Some(1));
+        assert_eq!(service.wifi_channel(&on(5935)), Some(2));
+        assert_eq!(service.wifi_channel(&on(7115)), Some(233));
+        assert!(service.is_wifi6e(&six));
+        assert!(!service.is_wifi6e(&on(5180)));
+        assert_eq!(service.classify_band(&on(900)), None);
+
+        let mut networks = mock_wifi_networks();
+        networks.push(six.clone());
+        assert_eq!(
+            service.filter_by_band(&networks, WifiBand::Band6GHz),
+            vec![six]
+        );
+        let two_four = service.filter_by_band(&networks, WifiBand::Band2_4GHz);
+        assert_eq!(two_four.len(), 2);
+    }
+
+    #[cfg(feature = "checksum")]
+    #[test]
+    fn test_checksum_detects_changes() {
+        let service = SensorService::new();
+        let sample = mock_accelerometer_moving();
+        let crc = service.checksum(&sample);
+        assert_eq!(crc, service.checksum(&sample.clone()));
+        assert!(service.verify_checksum(&sample, crc));
+
+        let flipped = AccelerometerData {
+            accuracy: sample.accuracy - 1,
+            ..sample.clone()
+        };
+        assert_ne!(service.checksum(&flipped), crc);
+        assert!(!service.verify_checksum(&flipped, crc));
+        // Matches a CRC32 over the JSON bytes computed independently.
+        assert_eq!(
+            crc,
+            crc32fast::hash(serde_json::to_string(&sample).unwrap().as_bytes())
+        );
+    }
+
+    #[test]
+    fn test_wifi_to_csv() {
+        let service = SensorService::new();
+        let mut networks = mock_wifi_networks();
+        networks[2].ssid = "Cafe, Downtown".to_string();
+        networks.push(WifiNetwork {
+            ssid: "Say \"hi\"".to_string(),
+            frequency: 900,
+            ..mock_wifi_networks()[0].clone()
+        });
+
+        let csv = service.wifi_to_csv(&networks);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), networks.len() + 1);
+        assert_eq!(
+            lines[0],
+            "ssid,bssid,signal_strength,frequency,security,channel,band"
+        );
+        assert_eq!(
+            lines[1],
+            "HomeNetwork,00:11:22:33:44:55,-45,2437,WPA2,6,2.4 GHz"
+        );
+        assert_eq!(
+            lines[3],
+            "\"Cafe, Downtown\",CC:DD:EE:FF:00:11,-80,2412,Open,1,2.4 GHz"
+        );
+        assert!(lines[4].starts_with("\"Say \"\"hi\"\"\","));
+        assert!(lines[4].ends_with(",WPA2,,Unknown"));
+        assert_eq!(service.wifi_to_csv(&[]).lines().count(), 1);
+    }
+
+    #[test]
+    fn test_relative_bearing() {
+        let service = SensorService::new();
+        let here = mock_gps_san_francisco();
+        let north = fix_at(&here, 0.0, 500.0, 0);
+        let east = fix_at(&here, 500.0, 0.0, 0);
+        let west = fix_at(&here, -500.0, 0.0, 0);
+
+        assert!(service.relative_bearing(&here, &north, 0.0).abs() < 0.1);
+        assert!((service.relative_bearing(&here, &east, 0.0) - 90.0).abs() < 0.1);
+        assert!((service.relative_bearing(&here, &west, 0.0) + 90.0).abs() < 0.1);
+        // Facing east, a target to the north is a quarter turn left.
+        assert!((service.relative_bearing(&here, &north, 90.0) + 90.0).abs() < 0.1);
+        assert!((service.relative_bearing(&here, &west, 350.0) + 80.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_to_jsonl() {
+        let service = SensorService::new();
+        let readings = vec![
+            mock_accelerometer_at_rest(),
+            mock_accelerometer_moving(),
+            mock_accelerometer_at_rest(),
+        ];
+        let jsonl = service.accelerometer_to_jsonl(&readings);
+        let lines: Vec<&str> = jsonl.lines().collect();
+        assert_eq!(lines.len(), readings.len());
+        for (line, reading) in lines.iter().zip(&readings) {
+            let parsed: AccelerometerData = serde_json::from_str(line).unwrap();
+            assert_eq!(&parsed, reading);
+        }
+        assert!(jsonl.ends_with('\n'));
+        assert_eq!(service.accelerometer_to_jsonl(&[]), "");
+
+        let fixes = service.to_jsonl(&[mock_gps_moving()]).unwrap();
+        assert_eq!(fixes.lines().count(), 1);
+        assert!(service.to_jsonl::<GpsData>(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_detect_anomalies() {
+        let service = SensorService::new();
+        let mut pressures = vec![
+            1013.0, 1013.2, 1012.9, 1013.1, 1013.0, 1012.8, 1013.1, 1013.0,
+        ];
+        pressures.insert(5, 990.0);
+        assert_eq!(service.detect_anomalies(&pressures, 2.5), vec![5]);
+
+        assert!(service.detect_anomalies(&[22.5; 10], 1.0).is_empty());
+        assert!(service.detect_anomalies(&[1013.25; 7], 0.5).is_empty());
+        assert!(service.detect_anomalies(&[], 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_station_to_qnh() {
+        let service = SensorService::new();
+        let sea_level = mock_pressure_sea_level();
+        assert_eq!(service.station_to_qnh(&sea_level, 0.0), sea_level.pressure);
+
+        // ISA pressure at 500 m is about 954.6 hPa.
+        let station = PressureData {
+            pressure: 954.6,
+            timestamp: 0,
+        };
+        let qnh = service.station_to_qnh(&station, 500.0);
+        assert!((qnh - 1013.25).abs() < 0.2, "qnh {qnh}");
+        assert!((service.altitude_qnh(&station, qnh) - 500.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_pressure_trend() {
+        let reading = |pressure: f32| PressureData {
+            pressure,
+            timestamp: 0,
+        };
+
+        let mut rising = PressureTrend::new(6, 0.5);
+        assert_eq!(rising.trend(), "Steady");
+        (0..10).for_each(|i| rising.push(&reading(1005.0 + i as f32 * 0.5)));
+        assert_eq!(rising.trend(), "Rising");
+
+        let mut falling = PressureTrend::new(6, 0.5);
+        (0..10).for_each(|i| falling.push(&reading(1020.0 - i as f32 * 0.5)));
+        assert_eq!(falling.trend(), "Falling");
+
+        // The same slow drift is steady under a coarser threshold.
+        let mut coarse = PressureTrend::new(6, 2.0);
+        (0..10).for_each(|i| coarse.push(&reading(1020.0 - i as f32 * 0.5)));
+        assert_eq!(coarse.trend(), "Steady");
+    }
+
+    #[test]
+    fn test_extract_features() {
+        let service = SensorService::new();
+        let walk = synthetic_walk(120.0, 50.0, 200);
+        let features = service.extract_features(&walk);
+        assert_eq!(features.len(), 28);
+        // z channel: mean ≈ g, std ≈ 2/√2, ~4 crossings/s, dominant 2 Hz.
+        let z = &features[14..21];
+        assert!((z[0] - GRAVITY).abs() < 0.05);
+        assert!((z[1] - std::f32::consts::SQRT_2).abs() < 0.05);
+        assert!((z[5] - 4.0).abs() < 0.3, "zcr {}", z[5]);
+        assert!((z[6] - 2.0).abs() < 0.1, "dominant {}", z[6]);
+
+        let still: Vec<AccelerometerData> = (0..100)
+            .map(|i| AccelerometerData {
+                timestamp: i * 20,
+                ..mock_accelerometer_at_rest()
+            })
+            .collect();
+        let features = service.extract_features(&still);
+        assert_eq!(features.len(), 28);
+        for channel in features.chunks(7) {
+            assert!(channel[1] < 1e-4, "std {}", channel[1]);
+            assert_eq!(channel[5], 0.0);
+        }
+        assert_eq!(service.extract_features(&[]), vec![0.0; 28]);
+    }
+
+    #[test]
+    fn test_dominant_frequency_on_long_window() {
+        // Five minutes at 100 Hz: probed at 256 frequencies, so the result
+        // is within one probe spacing (100 / 512 Hz) of the true 1.7 Hz.
+        let values: Vec<f32> = (0..30_000)
+            .map(|i| (2.0 * std::f32::consts::PI * 1.7 * i as f32 / 100.0).sin())
+            .collect();
+        let frequency = dominant_frequency(&values, 100.0);
+        assert!(
+            (frequency - 1.7).abs() < 100.0 / 512.0,
+            "dominant {frequency}"
+        );
+        assert_eq!(dominant_frequency(&[1.0; 1_000], 100.0), 0.0);
+    }
+
+    #[test]
+    fn test_validate_magnetometer() {
+        let service = SensorService::new();
+        assert!(service
+            .validate_magnetometer(&mock_magnetometer_north())
+            .is_ok());
+
+        let spun = MagnetometerData {
+            heading: 400.0,
+            ..mock_magnetometer_north()
+        };
+        let err = service.validate_magnetometer(&spun).unwrap_err();
+        assert!(err.to_string().contains("Heading out of range"));
+
+        let saturated = MagnetometerData {
+            x: 2000.0,
+            ..mock_magnetometer_north()
+        };
+        assert!(service.validate_magnetometer(&saturated).is_err());
+        let garbage = MagnetometerData {
+            y: f32::NAN,
+            ..mock_magnetometer_north()
+        };
+        assert!(service.validate_magnetometer(&garbage).is_err());
+    }
+
+    #[test]
+    fn test_grid_convergence() {
+        let service = SensorService::new();
+        // San Francisco in UTM zone 10 (central meridian -123°): 0.58° east
+        // of it at 37.77° N gives about +0.36°.
+        let sf = mock_gps_san_francisco();
+        let convergence = service.grid_convergence(&sf, -123.0);
+        assert!((convergence - 0.356).abs() < 0.001, "{convergence}");
+        assert!(service.grid_convergence(&sf, sf.longitude).abs() < 1e-12);
+        assert!(service.grid_convergence(&sf, -122.0) < 0.0);
+
+        // 13° E declination, then 0.36° of convergence back toward grid north.
+        let grid = service.heading_to_grid(&mock_magnetometer_north(), 13.0, &sf, -123.0);
+        assert!((grid - 12.644).abs() < 0.01, "{grid}");
+        let wrapped = service.heading_to_grid(&mock_magnetometer_north(), 0.0, &sf, -123.0);
+        assert!((wrapped - 359.644).abs() < 0.01, "{wrapped}");
+    }
+
+    #[test]
+    fn test_validate_timestamp() {
+        let service = SensorService::new();
+        let now = mock_accelerometer_at_rest().timestamp;
+        assert!(service.validate_timestamp(now, now).is_ok());
+        assert!(service.validate_timestamp(now + 60_000, now).is_ok());
+        assert!(service.validate_timestamp(-1, now).is_err());
+
+        let far_future = now + 25 * 60 * 60 * 1000;
+        let err = service.validate_timestamp(far_future, now).unwrap_err();
+        assert!(matches!(err, SensorError::DataError(_)));
+        assert!(service.validate_timestamp(i64::MAX, i64::MAX).is_ok());
+    }
+
+    #[test]
+    fn test_track_recorder_matches_batch() {
+        let service = SensorService::new();
+        let origin = mock_gps_san_francisco();
+        let fixes = [
+            fix_at(&origin, 0.0, 0.0, 0),
+            fix_at(&origin, 30.0, 0.0, 10_000),
+            fix_at(&origin, 30.0, 80.0, 20_000),
+            fix_at(&origin, 50.0, 80.0, 30_000),
+        ];
+
+        let mut recorder = TrackRecorder::new();
+        for (i, fix) in fixes.iter().enumerate() {
+            recorder.push(fix.clone());
+            let prefix = &fixes[..=i];
+            let distance: f64 = prefix
+                .windows(2)
+                .map(|pair| service.distance_between(&pair[0], &pair[1]))
+                .sum();
+            let max_speed = prefix
+                .windows(2)
+                .map(|pair| service.speed_from_fixes(&pair[0], &pair[1]).unwrap())
+                .fold(0.0, f32::max);
+
+            let stats = recorder.stats();
+            assert_eq!(stats.fix_count, i + 1);
+            assert!((stats.distance_m - distance).abs() < 1e-9);
+            assert_eq!(stats.duration_ms, fix.timestamp);
+            assert_eq!(stats.max_speed_mps, max_speed);
+            assert_eq!(stats, service.route_summary(prefix));
+        }
+        let stats = recorder.stats();
+        assert!((stats.max_speed_mps - 8.0).abs() < 0.01);
+        assert!((stats.average_speed_mps() - 130.0 / 30.0).abs() < 0.01);
+
+        // A stale fix is ignored.
+        recorder.push(fix_at(&origin, 500.0, 0.0, 5_000));
+        assert_eq!(recorder.stats(), stats);
+    }
+
+    #[test]
+    fn test_detect_orientation() {
+        let service = SensorService::new();
+        let gravity_along = |x: f32, y: f32, z: f32| AccelerometerData {
+            x,
+            y,
+            z,
+            ..mock_accelerometer_at_rest()
+        };
+        assert_eq!(
+            service.detect_orientation(&gravity_along(0.5, GRAVITY, 1.0)),
+            "PortraitUp"
+        );
+        assert_eq!(
+            service.detect_orientation(&mock_accelerometer_at_rest()),
+            "FaceUp"
+        );
+        assert_eq!(
+            service.detect_orientation(&gravity_along(0.0, -GRAVITY, 0.0)),
+            "PortraitDown"
+        );
+        assert_eq!(
+            service.detect_orientation(&gravity_along(GRAVITY, 0.0, 0.0)),
+            "LandscapeLeft"
+        );
+        assert_eq!(
+            service.detect_orientation(&gravity_along(-GRAVITY, 0.0, 0.0)),
+            "LandscapeRight"
+        );
+        assert_eq!(
+            service.detect_orientation(&gravity_along(0.0, 0.0, -GRAVITY)),
+            "FaceDown"
+        );
+    }
+
+    #[test]
+    fn test_comfort_alerts() {
+        let service = SensorService::new();
+        let temps: Vec<TemperatureData> = [22.0, 24.0, 31.5, 32.0, 25.0, 23.0]
+            .iter()
+            .enumerate()
+            .map(|(i, &temperature)| TemperatureData {
+                temperature,
+                timestamp: i as i64 * 60_000,
+            })
+            .collect();
+        // Humidity is logged half as often and a little later.
+        let humidities: Vec<HumidityData> = [55.0, 58.0, 35.0]
+            .iter()
+            .enumerate()
+            .map(|(i, &humidity)| HumidityData {
+                humidity,
+                timestamp: i as i64 * 120_000 + 5_000,
+            })
+            .collect();
+
+        let alerts = service.comfort_alerts(&temps, &humidities, (18.0, 28.0), (40.0, 70.0));
+        assert_eq!(
+            alerts,
+            vec![
+                (120_000, "Temperature 31.5°C above 18.0-28.0°C".to_string()),
+                (240_000, "Humidity 35.0% below 40.0-70.0%".to_string()),
+            ]
+        );
+        assert!(service
+            .comfort_alerts(&temps[..2], &humidities[..1], (18.0, 28.0), (40.0, 70.0))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_shake_detector_fires_once_per_burst() {
+        let mut detector = ShakeDetector::new(8.0, 4, 500);
+        let sample = |z: f32, timestamp: i64| AccelerometerData {
+            x: 0.0,
+            y: 0.0,
+            z,
+            timestamp,
+            accuracy: 3,
+        };
+
+        // A second of vigorous shaking at 50 Hz, then rest.
+        let burst = (0..50).map(|i| sample(if i % 2 == 0 { 25.0 } else { 2.0 }, i * 20));
+        let rest = (50..150).map(|i| sample(GRAVITY, i * 20));
+        let fired: Vec<i64> = burst
+            .chain(rest)
+            .filter(|s| detector.process(s))
+            .map(|s| s.timestamp)
+            .collect();
+        assert_eq!(fired, vec![80]);
+
+        // Once the window has emptied, a new burst fires again.
+        assert!((150..160)
+            .any(|i| detector.process(&sample(if i % 2 == 0 { 25.0 } else { 2.0 }, i * 20))));
+    }
+
+    #[test]
+    fn test_shake_detector_ignores_gentle_motion() {
+        let mut detector = ShakeDetector::new(8.0, 4, 500);
+        for accel in synthetic_walk(110.0, 50.0, 500) {
+            assert!(!detector.process(&accel));
+        }
+    }
+
+    #[test]
+    fn test_track_distance_filtered_skips_teleport() {
+        let service = SensorService::new();
+        let origin = mock_gps_san_francisco();
+        // Walking north at 1.5 m/s with one fix that jumps 2 km east.
+        let mut track: Vec<GpsData> = (0..10)
+            .map(|i| fix_at(&origin, 0.0, i as f64 * 15.0, i * 10_000))
+            .collect();
+        track[5] = fix_at(&origin, 2_000.0, 75.0, 50_000);
+
+        let unfiltered: f64 = track
+            .windows(2)
+            .map(|pair| service.distance_between(&pair[0], &pair[1]))
+            .sum();
+        let filtered = service.track_distance_filtered(&track, 10.0);
+        assert!(unfiltered > 4_000.0);
+        assert!((filtered - 135.0).abs() < 0.5, "got {filtered}");
+        assert_eq!(service.track_distance_filtered(&track[..1], 10.0), 0.0);
+    }
+
+    #[test]
+    fn test_gps_to_utm_reference_points() {
+        let service = SensorService::new();
+        let at = |latitude: f64, longitude: f64| GpsData {
+            latitude,
+            longitude,
+            ..mock_gps_san_francisco()
+        };
+
+        // CN Tower, Toronto: 17T 630084 4833439 (Wikipedia's UTM example).
+        let (zone, band, easting, northing) =
+            service.gps_to_utm(&at(43.642567, -79.387139)).unwrap();
+        assert_eq!((zone, band), (17, 'T'));
+        assert!((easting - 630_084.0).abs() < 1.0, "easting {easting}");
+        assert!((northing - 4_833_439.0).abs() < 1.0, "northing {northing}");
+
+        // Flinders Peak, Victoria: 37°57'03.7203"S 144°25'29.5244"E is
+        // 55H 273741.297 5796489.777, the worked example in the ICSM GDA
+        // Technical Manual (GRS80, which matches WGS84 to well under 1 mm).
+        let flinders_peak = at(
+            -(37.0 + 57.0 / 60.0 + 3.7203 / 3600.0),
+            144.0 + 25.0 / 60.0 + 29.5244 / 3600.0,
+        );
+        let (zone, band, easting, northing) = service.gps_to_utm(&flinders_peak).unwrap();
+        assert_eq!((zone, band), (55, 'H'));
+        assert!((easting - 273_741.297).abs() < 0.01, "easting {easting}");
+        assert!(
+            (northing - 5_796_489.777).abs() < 0.01,
+            "northing {northing}"
+        );
+
+        // On the equator at a central meridian the grid origin is exact.
+        let (zone, band, easting, northing) = service.gps_to_utm(&at(0.0, 3.0)).unwrap();
+        assert_eq!((zone, band), (31, 'N'));
+        assert!((easting - 500_000.0).abs() < 1e-6 && northing.abs() < 1e-6);
+
+        // Bergen sits in the widened zone 32V.
+        assert_eq!(service.gps_to_utm(&at(60.39, 5.32)).unwrap().0, 32);
+    }
+
+    #[test]
+    fn test_gps_to_utm_rejects_polar_latitudes() {
+        let service = SensorService::new();
+        for latitude in [84.5, -80.5, 90.0] {
+            let fix = GpsData {
+                latitude,
+                ..mock_gps_san_francisco()
+            };
+            assert!(matches!(
+                service.gps_to_utm(&fix),
+                Err(SensorError::DataError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_estimate_step_length_grows_with_amplitude() {
+        let service = SensorService::new();
+        let walk = synthetic_walk(110.0, 50.0, 500);
+        let scaled = |factor: f32| -> Vec<AccelerometerData> {
+            walk.iter()
+                .map(|s| AccelerometerData {
+                    z: GRAVITY + factor * (s.z - GRAVITY),
+                    ..s.clone()
+                })
+                .collect()
+        };
+
+        let stroll = service.estimate_step_length(&walk, 1.75).unwrap();
+        let stride = service.estimate_step_length(&scaled(3.0), 1.75).unwrap();
+        // Peak-to-valley of 4 m/s²: 0.4375 · 4^¼ ≈ 0.62 m.
+        assert!((stroll - 0.619).abs() < 0.01, "stroll {stroll}");
+        assert!(stride > stroll);
+        assert!(service.estimate_step_length(&walk, 1.9).unwrap() > stroll);
+        assert_eq!(service.estimate_step_length(&scaled(0.0), 1.75), Ok(0.0));
+        assert!(matches!(
+            service.estimate_step_length(&[], 1.75),
+            Err(SensorError::EmptyInput(_))
+        ));
+        assert!(matches!(
+            service.estimate_step_length(&walk, 0.0),
+            Err(SensorError::DataError(_))
+        ));
+    }
+
+    #[test]
+    fn test_gps_to_geohash() {
+        let service = SensorService::new();
+        let san_francisco = mock_gps_san_francisco();
+        assert_eq!(service.gps_to_geohash(&san_francisco, 5), "9q8yy");
+        assert_eq!(service.gps_to_geohash(&san_francisco, 12), "9q8yyk8ytpxr");
+        assert_eq!(service.gps_to_geohash(&san_francisco, 20).len(), 12);
+        assert_eq!(service.gps_to_geohash(&san_francisco, 0), "9");
+
+        // Wikipedia's worked example, near Aalborg.
+        let aalborg = GpsData {
+            latitude: 57.64911,
+            longitude: 10.40744,
+            ..san_francisco.clone()
+        };
+        assert_eq!(service.gps_to_geohash(&aalborg, 11), "u4pruydqqvj");
+
+        let long = service.gps_to_geohash(&san_francisco, 12);
+        for precision in 1..12 {
+            assert!(long.starts_with(&service.gps_to_geohash(&san_francisco, precision)));
+        }
+    }
+}
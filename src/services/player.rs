@@ -0,0 +1,126 @@
+//! Real-time replay of recorded sessions.
+
+use crate::models::{SensorReading, SensorSession, SensorType};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Replays a [`SensorSession`] sample by sample, waiting between samples for
+/// as long as elapsed between them in the recording.
+///
+/// Samples from all sensors are merged in timestamp order; samples sharing a
+/// timestamp keep the sensor order of the session struct.
+#[derive(Debug, Clone)]
+pub struct SessionPlayer {
+    queue: VecDeque<(i64, SensorType, Value)>,
+    last_timestamp: Option<i64>,
+}
+
+impl SessionPlayer {
+    pub fn new(session: SensorSession) -> Self {
+        let mut samples = Vec::new();
+        push_all(
+            &mut samples,
+            SensorType::Accelerometer,
+            &session.accelerometer,
+        );
+        push_all(
+            &mut samples,
+            SensorType::Magnetometer,
+            &session.magnetometer,
+        );
+        push_all(&mut samples, SensorType::Gps, &session.gps);
+        push_all(&mut samples, SensorType::Temperature, &session.temperature);
+        push_all(&mut samples, SensorType::Pressure, &session.pressure);
+        samples.sort_by_key(|(timestamp, _, _)| *timestamp);
+        Self {
+            queue: samples.into(),
+            last_timestamp: None,
+        }
+    }
+
+    /// Number of samples not yet replayed.
+    pub fn remaining(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Waits until the next sample is due and returns it as JSON, or `None`
+    /// once the recording is exhausted. The first sample is returned at once.
+    pub async fn next(&mut self) -> Option<(SensorType, Value)> {
+        let (timestamp, sensor, value) = self.queue.pop_front()?;
+        if let Some(last) = self.last_timestamp {
+            let delay_ms = (timestamp - last).max(0) as u64;
+            if delay_ms > 0 {
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            }
+        }
+        self.last_timestamp = Some(timestamp);
+        Some((sensor, value))
+    }
+}
+
+fn push_all<T: Serialize + SensorReading>(
+    out: &mut Vec<(i64, SensorType, Value)>,
+    sensor: SensorType,
+    samples: &[T],
+) {
+    out.extend(samples.iter().filter_map(|sample| {
+        let value = serde_json::to_value(sample).ok()?;
+        Some((sample.timestamp(), sensor, value))
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{GpsData, PressureData, TemperatureData};
+    use tokio::time::Instant;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_session_player_replays_in_order_with_timing() {
+        let mut session = SensorSession::new("pixel-7");
+        session.pressure.push(PressureData {
+            pressure: 1000.0,
+            timestamp: 3_000,
+        });
+        session.temperature.push(TemperatureData {
+            temperature: 20.0,
+            timestamp: 1_000,
+        });
+        session.gps.push(GpsData {
+            latitude: 37.0,
+            longitude: -122.0,
+            altitude: None,
+            accuracy: 5.0,
+            speed: None,
+            bearing: None,
+            timestamp: 2_500,
+        });
+        let mut player = SessionPlayer::new(session);
+        assert_eq!(player.remaining(), 3);
+
+        let start = Instant::now();
+        let mut replayed = Vec::new();
+        while let Some((sensor, value)) = player.next().await {
+            replayed.push((
+                sensor,
+                value["timestamp"].as_i64().unwrap(),
+                start.elapsed(),
+            ));
+        }
+
+        let order: Vec<(SensorType, i64)> = replayed.iter().map(|(s, t, _)| (*s, *t)).collect();
+        assert_eq!(
+            order,
+            vec![
+                (SensorType::Temperature, 1_000),
+                (SensorType::Gps, 2_500),
+                (SensorType::Pressure, 3_000),
+            ]
+        );
+        assert_eq!(replayed[0].2, Duration::ZERO);
+        assert_eq!(replayed[1].2, Duration::from_millis(1_500));
+        assert_eq!(replayed[2].2, Duration::from_millis(2_000));
+    }
+}
@@ -0,0 +1,287 @@
+//! Pluggable destinations for sensor samples.
+//!
+//! A pipeline writes through [`SampleSink`] and stays unaware of whether the
+//! samples end up in memory, a file or a socket.
+
+use super::SensorError;
+use crate::models::{
+    AccelerometerData, GpsData, GyroscopeData, HumidityData, MagnetometerData, PressureData,
+    TemperatureData,
+};
+use std::io::Write;
+
+/// A destination that accepts samples of every sensor type.
+pub trait SampleSink {
+    fn write_accelerometer(&mut self, data: &AccelerometerData) -> Result<(), SensorError>;
+    fn write_gyroscope(&mut self, data: &GyroscopeData) -> Result<(), SensorError>;
+    fn write_magnetometer(&mut self, data: &MagnetometerData) -> Result<(), SensorError>;
+    fn write_gps(&mut self, data: &GpsData) -> Result<(), SensorError>;
+    fn write_temperature(&mut self, data: &TemperatureData) -> Result<(), SensorError>;
+    fn write_pressure(&mut self, data: &PressureData) -> Result<(), SensorError>;
+    fn write_humidity(&mut self, data: &HumidityData) -> Result<(), SensorError>;
+}
+
+/// Keeps every sample in memory, one vector per sensor type, in write order.
+#[derive(Debug, Clone, Default)]
+pub struct VecSink {
+    accelerometer: Vec<AccelerometerData>,
+    gyroscope: Vec<GyroscopeData>,
+    magnetometer: Vec<MagnetometerData>,
+    gps: Vec<GpsData>,
+    temperature: Vec<TemperatureData>,
+    pressure: Vec<PressureData>,
+    humidity: Vec<HumidityData>,
+}
+
+impl VecSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn accelerometer(&self) -> &[AccelerometerData] {
+        &self.accelerometer
+    }
+
+    pub fn gyroscope(&self) -> &[GyroscopeData] {
+        &self.gyroscope
+    }
+
+    pub fn magnetometer(&self) -> &[MagnetometerData] {
+        &self.magnetometer
+    }
+
+    pub fn gps(&self) -> &[GpsData] {
+        &self.gps
+    }
+
+    pub fn temperature(&self) -> &[TemperatureData] {
+        &self.temperature
+    }
+
+    pub fn pressure(&self) -> &[PressureData] {
+        &self.pressure
+    }
+
+    pub fn humidity(&self) -> &[HumidityData] {
+        &self.humidity
+    }
+
+    /// Total number of samples across all sensor types.
+    pub fn len(&self) -> usize {
+        self.accelerometer.len()
+            + self.gyroscope.len()
+            + self.magnetometer.len()
+            + self.gps.len()
+            + self.temperature.len()
+            + self.pressure.len()
+            + self.humidity.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl SampleSink for VecSink {
+    fn write_accelerometer(&mut self, data: &AccelerometerData) -> Result<(), SensorError> {
+        self.accelerometer.push(data.clone());
+        Ok(())
+    }
+
+    fn write_gyroscope(&mut self, data: &GyroscopeData) -> Result<(), SensorError> {
+        self.gyroscope.push(data.clone());
+        Ok(())
+    }
+
+    fn write_magnetometer(&mut self, data: &MagnetometerData) -> Result<(), SensorError> {
+        self.magnetometer.push(data.clone());
+        Ok(())
+    }
+
+    fn write_gps(&mut self, data: &GpsData) -> Result<(), SensorError> {
+        self.gps.push(data.clone());
+        Ok(())
+    }
+
+    fn write_temperature(&mut self, data: &TemperatureData) -> Result<(), SensorError> {
+        self.temperature.push(data.clone());
+        Ok(())
+    }
+
+    fn write_pressure(&mut self, data: &PressureData) -> Result<(), SensorError> {
+        self.pressure.push(data.clone());
+        Ok(())
+    }
+
+    fn write_humidity(&mut self, data: &HumidityData) -> Result<(), SensorError> {
+        self.humidity.push(data.clone());
+        Ok(())
+    }
+}
+
+/// Writes samples as CSV rows to any [`Write`] destination.
+///
+/// Sensor types share one stream, so there is no header row. Each row starts
+/// with the sensor type and timestamp, followed by that type's values in
+/// declaration order:
+///
+/// ```text
+/// accelerometer,1000,0,0,9.81,3
+/// gps,1000,37.7749,-122.4194,16,5,,
+/// ```
+///
+/// Missing optional values are left empty. Write failures surface as
+/// `DataError`.
+#[derive(Debug)]
+pub struct WriterSink<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> WriterSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_row(
+        &mut self,
+        kind: &str,
+        timestamp: i64,
+        values: &[String],
+    ) -> Result<(), SensorError> {
+        writeln!(self.writer, "{kind},{timestamp},{}", values.join(","))
+            .map_err(|e| SensorError::DataError(e.to_string()))
+    }
+}
+
+impl<W: Write> SampleSink for WriterSink<W> {
+    fn write_accelerometer(&mut self, data: &AccelerometerData) -> Result<(), SensorError> {
+        let values = [
+            data.x.to_string(),
+            data.y.to_string(),
+            data.z.to_string(),
+            data.accuracy.to_string(),
+        ];
+        self.write_row("accelerometer", data.timestamp, &values)
+    }
+
+    fn write_gyroscope(&mut self, data: &GyroscopeData) -> Result<(), SensorError> {
+        let values = [
+            data.x.to_string(),
+            data.y.to_string(),
+            data.z.to_string(),
+            data.accuracy.to_string(),
+        ];
+        self.write_row("gyroscope", data.timestamp, &values)
+    }
+
+    fn write_magnetometer(&mut self, data: &MagnetometerData) -> Result<(), SensorError> {
+        let values = [
+            data.x.to_string(),
+            data.y.to_string(),
+            data.z.to_string(),
+            data.heading.to_string(),
+            data.accuracy.to_string(),
+        ];
+        self.write_row("magnetometer", data.timestamp, &values)
+    }
+
+    fn write_gps(&mut self, data: &GpsData) -> Result<(), SensorError> {
+        let values = [
+            data.latitude.to_string(),
+            data.longitude.to_string(),
+            optional(data.altitude),
+            data.accuracy.to_string(),
+            optional(data.speed),
+            optional(data.bearing),
+        ];
+        self.write_row("gps", data.timestamp, &values)
+    }
+
+    fn write_temperature(&mut self, data: &TemperatureData) -> Result<(), SensorError> {
+        self.write_row(
+            "temperature",
+            data.timestamp,
+            &[data.temperature.to_string()],
+        )
+    }
+
+    fn write_pressure(&mut self, data: &PressureData) -> Result<(), SensorError> {
+        self.write_row("pressure", data.timestamp, &[data.pressure.to_string()])
+    }
+
+    fn write_humidity(&mut self, data: &HumidityData) -> Result<(), SensorError> {
+        self.write_row("humidity", data.timestamp, &[data.humidity.to_string()])
+    }
+}
+
+/// An optional CSV value, empty when absent.
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(String::new, |v| v.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks::*;
+
+    #[test]
+    fn test_vec_sink_round_trip() {
+        let mut sink = VecSink::new();
+        assert!(sink.is_empty());
+
+        let accel = mock_accelerometer_moving();
+        let fix = mock_gps_san_francisco();
+        let temperature = TemperatureData {
+            temperature: 21.5,
+            timestamp: 1_000,
+        };
+        sink.write_accelerometer(&accel).unwrap();
+        let at_rest = mock_accelerometer_at_rest();
+        sink.write_accelerometer(&at_rest).unwrap();
+        sink.write_gps(&fix).unwrap();
+        sink.write_temperature(&temperature).unwrap();
+
+        assert_eq!(sink.len(), 4);
+        assert_eq!(sink.accelerometer()[0], accel);
+        assert_eq!(sink.accelerometer()[1], at_rest);
+        assert_eq!(sink.gps(), &[fix]);
+        assert_eq!(sink.temperature(), &[temperature]);
+        assert!(sink.magnetometer().is_empty() && sink.humidity().is_empty());
+    }
+
+    #[test]
+    fn test_writer_sink_csv_rows() {
+        let mut sink = WriterSink::new(Vec::new());
+        sink.write_accelerometer(&AccelerometerData {
+            x: 0.0,
+            y: 0.0,
+            z: 9.81,
+            timestamp: 1_000,
+            accuracy: 3,
+        })
+        .unwrap();
+        sink.write_gps(&GpsData {
+            timestamp: 1_000,
+            ..mock_gps_san_francisco()
+        })
+        .unwrap();
+        sink.write_pressure(&PressureData {
+            pressure: 1013.25,
+            timestamp: 2_000,
+        })
+        .unwrap();
+
+        let csv = String::from_utf8(sink.into_inner()).unwrap();
+        assert_eq!(
+            csv,
+            "accelerometer,1000,0,0,9.81,3\n\
+             gps,1000,37.7749,-122.4194,16,5,0,\n\
+             pressure,2000,1013.25\n"
+        );
+    }
+}